@@ -2,7 +2,7 @@
 
 use icebook::prelude::*;
 use iced::widget::{button, column, container, row, text, text_input};
-use iced::{Color, Element, Length};
+use iced::{Color, Element, Length, Subscription};
 
 // ============================================================================
 // Theme Provider
@@ -31,6 +31,25 @@ impl SimpleTheme {
             primary: Color::from_rgb(0.2, 0.4, 0.9),
         }
     }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            primary: Color::from_rgb(1.0, 0.85, 0.0),
+        }
+    }
+
+    /// Brand theme from the W3C design tokens in `brand_tokens.json`
+    pub fn brand() -> Self {
+        Self {
+            background: BRAND_TOKENS
+                .color("color.background")
+                .unwrap_or(Color::WHITE),
+            text: BRAND_TOKENS.color("color.text").unwrap_or(Color::BLACK),
+            primary: BRAND_TOKENS.color("color.primary").unwrap_or(Color::BLACK),
+        }
+    }
 }
 
 /// Theme provider implementation
@@ -40,6 +59,40 @@ static DARK_THEME: once_cell::sync::Lazy<SimpleTheme> =
     once_cell::sync::Lazy::new(SimpleTheme::dark);
 static LIGHT_THEME: once_cell::sync::Lazy<SimpleTheme> =
     once_cell::sync::Lazy::new(SimpleTheme::light);
+static HIGH_CONTRAST_THEME: once_cell::sync::Lazy<SimpleTheme> =
+    once_cell::sync::Lazy::new(SimpleTheme::high_contrast);
+static HIGH_CONTRAST_SIDEBAR: once_cell::sync::Lazy<DataSidebarTheme> =
+    once_cell::sync::Lazy::new(|| {
+        DataSidebarTheme::from_toml(include_str!("high_contrast_sidebar.toml"))
+            .expect("valid high contrast sidebar theme")
+    });
+static BRAND_TOKENS: once_cell::sync::Lazy<TokenTable> = once_cell::sync::Lazy::new(|| {
+    TokenTable::from_json(include_str!("brand_tokens.json")).expect("valid brand tokens")
+});
+static BRAND_THEME: once_cell::sync::Lazy<SimpleTheme> =
+    once_cell::sync::Lazy::new(SimpleTheme::brand);
+static BRAND_SIDEBAR: once_cell::sync::Lazy<DataSidebarTheme> = once_cell::sync::Lazy::new(|| {
+    BRAND_TOKENS
+        .sidebar_theme(&SidebarTokenPaths::default())
+        .expect("brand tokens define the sidebar colors")
+});
+
+/// Status colors shared by all example themes
+const SUCCESS: Color = Color::from_rgb(0.2, 0.8, 0.2);
+const ERROR: Color = Color::from_rgb(0.9, 0.2, 0.2);
+const WARNING: Color = Color::from_rgb(0.9, 0.7, 0.1);
+
+const HIGH_CONTRAST: ThemeInfo = ThemeInfo {
+    id: "high-contrast",
+    label: "High Contrast",
+    brightness: Brightness::Dark,
+};
+
+const BRAND: ThemeInfo = ThemeInfo {
+    id: "brand",
+    label: "Brand",
+    brightness: Brightness::Light,
+};
 
 impl ThemeProvider for SimpleThemeProvider {
     type Theme = SimpleTheme;
@@ -50,6 +103,68 @@ impl ThemeProvider for SimpleThemeProvider {
             Brightness::Light => &LIGHT_THEME,
         }
     }
+
+    fn themes() -> Vec<ThemeInfo> {
+        vec![ThemeInfo::LIGHT, ThemeInfo::DARK, HIGH_CONTRAST, BRAND]
+    }
+
+    fn get_named_theme(theme: &ThemeInfo) -> &'static Self::Theme {
+        match theme.id {
+            "high-contrast" => &HIGH_CONTRAST_THEME,
+            "brand" => &BRAND_THEME,
+            _ => Self::get_theme(theme.brightness),
+        }
+    }
+
+    fn get_named_sidebar_theme(theme: &ThemeInfo) -> &'static dyn SidebarTheme {
+        match theme.id {
+            "high-contrast" => &*HIGH_CONTRAST_SIDEBAR,
+            "brand" => &*BRAND_SIDEBAR,
+            _ => Self::get_sidebar_theme(theme.brightness),
+        }
+    }
+
+    fn get_iced_theme(info: &ThemeInfo) -> iced::Theme {
+        let theme = Self::get_named_theme(info);
+        iced::Theme::custom(
+            info.label,
+            iced::theme::Palette {
+                background: theme.background,
+                text: theme.text,
+                primary: theme.primary,
+                success: SUCCESS,
+                warning: WARNING,
+                danger: ERROR,
+            },
+        )
+    }
+
+    fn design_tokens(info: &ThemeInfo) -> Vec<DesignToken> {
+        if info.id == "brand" {
+            return BRAND_TOKENS.design_tokens();
+        }
+
+        let theme = Self::get_named_theme(info);
+        vec![
+            DesignToken::background_color("color.background", theme.background),
+            DesignToken::text_color("color.text", theme.text),
+            DesignToken::color("color.primary", theme.primary),
+            DesignToken::color("color.success", SUCCESS),
+            DesignToken::color("color.error", ERROR),
+            DesignToken::color("color.warning", WARNING),
+            DesignToken::spacing("spacing.xs", 4.0),
+            DesignToken::spacing("spacing.sm", 8.0),
+            DesignToken::spacing("spacing.md", 16.0),
+            DesignToken::spacing("spacing.lg", 32.0),
+            DesignToken::radius("radius.sm", 4.0),
+            DesignToken::radius("radius.md", 8.0),
+            DesignToken::font_size("font.caption", 12.0),
+            DesignToken::font_size("font.body", 16.0),
+            DesignToken::font_size("font.subheading", 20.0),
+            DesignToken::font_size("font.heading", 24.0),
+            DesignToken::font_size("font.title", 28.0),
+        ]
+    }
 }
 
 // ============================================================================
@@ -71,11 +186,9 @@ impl Story<SimpleTheme> for ButtonStory {
     type Message = ButtonMessage;
 
     fn meta() -> StoryMeta {
-        StoryMeta {
-            id: "buttons",
-            title: "Buttons",
-            category: "Components",
-        }
+        // Open on a checkerboard, to spot translucent button states
+        StoryMeta::new("buttons", "Buttons", "Components")
+            .background(CanvasBackground::Checkerboard)
     }
 
     fn update(&mut self, message: Self::Message) {
@@ -87,16 +200,23 @@ impl Story<SimpleTheme> for ButtonStory {
     }
 
     fn view(&self, theme: &SimpleTheme) -> Element<'_, Self::Message> {
+        self.view_with_padding(theme, 10)
+    }
+}
+
+impl ButtonStory {
+    /// Render with the button padding of the "Density" toolbar item
+    fn view_with_padding(&self, theme: &SimpleTheme, padding: u16) -> Element<'_, ButtonMessage> {
         let primary = theme.primary;
         column![
             text("Button Story").size(24).color(theme.text),
             text(format!("Click count: {}", self.click_count)).color(theme.text),
             button(text("Click me!").color(theme.text))
                 .on_press(ButtonMessage::Clicked)
-                .padding(10),
+                .padding(padding),
             button(text("Primary Button").color(Color::WHITE))
                 .on_press(ButtonMessage::Clicked)
-                .padding(10)
+                .padding(padding)
                 .style(move |_theme, _status| button::Style {
                     background: Some(primary.into()),
                     text_color: Color::WHITE,
@@ -123,11 +243,7 @@ impl Story<SimpleTheme> for InputStory {
     type Message = InputMessage;
 
     fn meta() -> StoryMeta {
-        StoryMeta {
-            id: "inputs",
-            title: "Inputs",
-            category: "Components",
-        }
+        StoryMeta::new("inputs", "Inputs", "Components").aliases(&["text-fields"])
     }
 
     fn update(&mut self, message: Self::Message) {
@@ -164,11 +280,7 @@ impl Story<SimpleTheme> for TypographyStory {
     type Message = TypographyMessage;
 
     fn meta() -> StoryMeta {
-        StoryMeta {
-            id: "typography",
-            title: "Typography",
-            category: "Foundation",
-        }
+        StoryMeta::new("typography", "Typography", "Foundation")
     }
 
     fn update(&mut self, _message: Self::Message) {}
@@ -189,62 +301,80 @@ impl Story<SimpleTheme> for TypographyStory {
     }
 }
 
-/// Colors story
+/// Menu story, navigated with the virtual gamepad
 #[derive(Default)]
-pub struct ColorsStory;
+pub struct MenuStory {
+    focused: usize,
+    selected: Option<usize>,
+    last_event: Option<GamepadEvent>,
+}
 
 #[derive(Debug, Clone)]
-pub enum ColorsMessage {}
+pub enum MenuMessage {
+    Gamepad(GamepadEvent),
+}
 
-impl Story<SimpleTheme> for ColorsStory {
-    type Message = ColorsMessage;
+const MENU_ITEMS: [&str; 4] = ["Library", "Store", "Friends", "Settings"];
+
+impl Story<SimpleTheme> for MenuStory {
+    type Message = MenuMessage;
 
     fn meta() -> StoryMeta {
-        StoryMeta {
-            id: "colors",
-            title: "Colors",
-            category: "Foundation",
-        }
+        StoryMeta::new("menu", "Menu", "Components")
     }
 
-    fn update(&mut self, _message: Self::Message) {}
+    fn update(&mut self, message: Self::Message) {
+        let MenuMessage::Gamepad(event) = message;
+        self.last_event = Some(event);
+
+        match event {
+            GamepadEvent::ButtonPressed(GamepadButton::DPad(Direction::Left)) => {
+                self.focused = self.focused.saturating_sub(1);
+            }
+            GamepadEvent::ButtonPressed(GamepadButton::DPad(Direction::Right)) => {
+                self.focused = (self.focused + 1).min(MENU_ITEMS.len() - 1);
+            }
+            GamepadEvent::ButtonPressed(GamepadButton::A) => self.selected = Some(self.focused),
+            GamepadEvent::ButtonPressed(GamepadButton::B) => self.selected = None,
+            _ => {}
+        }
+    }
 
     fn view(&self, theme: &SimpleTheme) -> Element<'_, Self::Message> {
-        let text_color = theme.text;
+        let primary = theme.primary;
+        let tiles = MENU_ITEMS.iter().enumerate().map(|(index, label)| {
+            let focused = index == self.focused;
+            container(text(*label).color(theme.text))
+                .padding(24)
+                .style(move |_| container::Style {
+                    border: iced::Border {
+                        color: if focused { primary } else { Color::TRANSPARENT },
+                        width: 3.0,
+                        radius: 8.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        });
 
         column![
-            text("Colors Story").size(24).color(text_color),
-            Self::color_swatch(theme.background, "Background", text_color),
-            Self::color_swatch(theme.text, "Text", text_color),
-            Self::color_swatch(theme.primary, "Primary", text_color),
-            Self::color_swatch(Color::from_rgb(0.2, 0.8, 0.2), "Success", text_color),
-            Self::color_swatch(Color::from_rgb(0.9, 0.2, 0.2), "Error", text_color),
-            Self::color_swatch(Color::from_rgb(0.9, 0.7, 0.1), "Warning", text_color),
+            text("Menu Story").size(24).color(theme.text),
+            text("Turn on \"Gamepad\" in the toolbar: ← → move, A selects, B clears")
+                .color(theme.text),
+            row(tiles).spacing(12),
+            text(match self.selected {
+                Some(index) => format!("Selected: {}", MENU_ITEMS[index]),
+                None => "Nothing selected".to_string(),
+            })
+            .color(theme.text),
+            text(format!("Last event: {:?}", self.last_event)).color(theme.text),
         ]
-        .spacing(12)
+        .spacing(16)
         .into()
     }
-}
 
-impl ColorsStory {
-    fn color_swatch<'a>(
-        color: Color,
-        name: &'a str,
-        text_color: Color,
-    ) -> Element<'a, ColorsMessage> {
-        row![
-            container(text(""))
-                .width(50)
-                .height(50)
-                .style(move |_| container::Style {
-                    background: Some(color.into()),
-                    ..Default::default()
-                }),
-            text(name).color(text_color),
-        ]
-        .spacing(8)
-        .align_y(iced::Alignment::Center)
-        .into()
+    fn subscription_with_context(&self, context: &StoryContext) -> Subscription<Self::Message> {
+        context.gamepad().map(MenuMessage::Gamepad)
     }
 }
 
@@ -257,7 +387,7 @@ pub enum ExampleMessage {
     Button(ButtonMessage),
     Input(InputMessage),
     Typography(TypographyMessage),
-    Colors(ColorsMessage),
+    Menu(MenuMessage),
 }
 
 #[derive(Default)]
@@ -265,7 +395,7 @@ pub struct ExampleStories {
     buttons: ButtonStory,
     inputs: InputStory,
     typography: TypographyStory,
-    colors: ColorsStory,
+    menu: MenuStory,
 }
 
 impl StoryRegistry for ExampleStories {
@@ -281,16 +411,24 @@ impl StoryRegistry for ExampleStories {
             ButtonStory::meta(),
             InputStory::meta(),
             TypographyStory::meta(),
-            ColorsStory::meta(),
+            MenuStory::meta(),
         ]
     }
 
+    fn toolbar() -> Vec<ToolbarItem> {
+        vec![ToolbarItem::choice(
+            "density",
+            "Density",
+            &["comfortable", "compact"],
+        )]
+    }
+
     fn update(&mut self, story_id: &str, message: Self::Message) {
         match (story_id, message) {
             ("buttons", ExampleMessage::Button(msg)) => self.buttons.update(msg),
             ("inputs", ExampleMessage::Input(msg)) => self.inputs.update(msg),
             ("typography", ExampleMessage::Typography(msg)) => self.typography.update(msg),
-            ("colors", ExampleMessage::Colors(msg)) => self.colors.update(msg),
+            ("menu", ExampleMessage::Menu(msg)) => self.menu.update(msg),
             _ => {}
         }
     }
@@ -300,10 +438,44 @@ impl StoryRegistry for ExampleStories {
             "buttons" => self.buttons.view(theme).map(ExampleMessage::Button),
             "inputs" => self.inputs.view(theme).map(ExampleMessage::Input),
             "typography" => self.typography.view(theme).map(ExampleMessage::Typography),
-            "colors" => self.colors.view(theme).map(ExampleMessage::Colors),
+            "menu" => self.menu.view(theme).map(ExampleMessage::Menu),
             _ => text("Story not found").into(),
         }
     }
+
+    fn view_with_toolbar<'a>(
+        &'a self,
+        story_id: &str,
+        theme: &'a SimpleTheme,
+        toolbar: &ToolbarValues,
+    ) -> Element<'a, Self::Message> {
+        match story_id {
+            "buttons" => {
+                let padding = match toolbar.choice("density") {
+                    Some("compact") => 4,
+                    _ => 10,
+                };
+                self.buttons
+                    .view_with_padding(theme, padding)
+                    .map(ExampleMessage::Button)
+            }
+            _ => self.view(story_id, theme),
+        }
+    }
+
+    fn subscription_with_context(
+        &self,
+        story_id: &str,
+        context: &StoryContext,
+    ) -> Subscription<Self::Message> {
+        match story_id {
+            "menu" => self
+                .menu
+                .subscription_with_context(context)
+                .map(ExampleMessage::Menu),
+            _ => self.subscription(story_id),
+        }
+    }
 }
 
 // ============================================================================
@@ -2,14 +2,43 @@
 //!
 //! Generic over the StoryRegistry provided by the consumer.
 
-use iced::widget::{column, container, row, scrollable, text};
-use iced::{Element, Length, Size, Subscription, Task};
+use std::collections::BTreeMap;
 
-use crate::preferences::Preferences;
+use iced::time::Instant;
+use iced::widget::{button, column, container, pick_list, row, stack, text, themer, Column, Row};
+use iced::{keyboard, window, Element, Length, Size, Subscription, Task};
+use once_cell::unsync::OnceCell;
+
+use crate::canvas::{
+    framed, CanvasBackground, CanvasMessage, GuideColors, Stage, StoryCanvas, Viewport,
+    DEFAULT_VIEWPORTS,
+};
+use crate::editor::{editor_view, EditorMessage, ThemeEditor, ThemeEdits};
+use crate::fonts::{
+    fonts_view, FontCoverage, FontRegistry, DEFAULT_FONT_SAMPLES, FONTS_STORY, FONTS_STORY_ID,
+};
+use crate::fullscreen;
+use crate::gamepad::{
+    gamepad_panel, key_input, Direction, GamepadButton, GamepadChannel, GamepadEvent,
+    GamepadMessage, VirtualGamepad,
+};
+use crate::perf::{perf_hud, PerfMonitor};
+use crate::preferences::{self, Preferences};
 use crate::routing;
-use crate::sidebar::{sidebar, NavItem, SidebarConfig, SidebarMessage, SidebarSection};
-use crate::story::{StoryMeta, StoryRegistry};
-use crate::theme::{Brightness, ThemeProvider};
+use crate::sidebar::{
+    focus_order, sidebar, NavItem, SidebarConfig, SidebarControls, SidebarFocus, SidebarMessage,
+    SidebarSection, NAV_LIST_ID, SEARCH_INPUT_ID,
+};
+use crate::story::{StoryContext, StoryMeta, StoryRegistry};
+use crate::theme::{
+    first_theme_with, Brightness, SidebarColors, SidebarTheme, ThemeInfo, ThemeMode, ThemeProvider,
+};
+use crate::tokens::{design_tokens_view, DESIGN_TOKENS_STORY, DESIGN_TOKENS_STORY_ID};
+use crate::toolbar::{
+    toolbar, ToolbarControls, ToolbarItem, ToolbarMessage, ToolbarValue, ToolbarValues,
+};
+use crate::transition::ThemeTransition;
+use crate::windows::{story_window_view, StoryWindow};
 
 /// The main Storybook application
 pub struct Storybook<S>
@@ -20,14 +49,64 @@ where
     stories: S,
     /// Currently selected story
     selected: String,
-    /// Current brightness mode
-    brightness: Brightness,
+    /// Route that did not match any story, shown on the not-found page
+    not_found: Option<NotFound>,
+    /// Current theme (resolved from the theme mode)
+    theme: ThemeInfo,
+    /// Last known system/browser brightness
+    system_brightness: Brightness,
+    /// Running animation of the chrome colors after a theme change
+    transition: Option<ThemeTransition>,
     /// User preferences
     preferences: Preferences,
     /// Cached sidebar config (owned data)
     sidebar_config: SidebarConfig,
     /// Current search query for filtering components
     search_query: String,
+    /// Render only the selected story, without sidebar or padding
+    embed: bool,
+    /// Render the selected story side by side in the current theme and another one
+    compare: bool,
+    /// Theme picked for the second compare pane (`None` for the default pick)
+    compare_theme: Option<ThemeInfo>,
+    /// Focus mode: the story fills the (fullscreen) window, without sidebar or toolbar
+    focus: bool,
+    /// Virtual gamepad (controller panel and keyboard mapping)
+    gamepad: VirtualGamepad,
+    /// Gamepad events for the selected story's subscriptions
+    gamepad_events: GamepadChannel,
+    /// Gamepad input from a device, from settings
+    gamepad_source: Option<fn() -> Subscription<GamepadEvent>>,
+    /// Sidebar control holding the gamepad focus (`None` while stories get the input)
+    sidebar_focus: Option<SidebarFocus>,
+    /// Timings of the selected story for the performance HUD
+    perf: PerfMonitor,
+    /// Whether the generated design tokens story is registered
+    design_tokens: bool,
+    /// Whether the generated fonts story is registered
+    fonts_story: bool,
+    /// Loaded fonts, shown on the generated fonts page
+    fonts: FontRegistry,
+    /// Sample strings for the glyph-coverage check of the fonts page
+    font_samples: &'static [(&'static str, &'static str)],
+    /// Glyph coverage of the font samples, computed when the fonts page is first shown
+    font_coverage: OnceCell<FontCoverage>,
+    /// Live theme editor (panel state and edited values)
+    editor: ThemeEditor,
+    /// Story canvas (viewport frame)
+    canvas: StoryCanvas,
+    /// Viewport presets offered in the toolbar
+    viewports: &'static [Viewport],
+    /// Consumer items of the toolbar
+    toolbar_items: Vec<ToolbarItem>,
+    /// Values of the consumer's toolbar items, passed to story views
+    toolbar_values: ToolbarValues,
+    /// Window title from settings
+    title: &'static str,
+    /// Main window, when running as a multi-window daemon (native only)
+    main_window: Option<window::Id>,
+    /// Stories popped out into their own windows
+    windows: BTreeMap<window::Id, StoryWindow>,
 }
 
 /// Messages for the Storybook application
@@ -35,12 +114,58 @@ where
 pub enum Message<M> {
     /// Message from a story component
     Story(M),
-    /// Toggle between light/dark mode
+    /// Toggle between light/dark mode (pins the first theme of the opposite brightness)
     ToggleBrightness,
+    /// Set the theme mode (follow the system, or a named theme)
+    SetThemeMode(ThemeMode),
+    /// The system color scheme was detected at startup (applied without a transition)
+    InitialBrightness(Brightness),
+    /// The system color scheme changed
+    SystemBrightnessChanged(Brightness),
+    /// Animation frame of a theme transition
+    TransitionFrame(Instant),
     /// Select a story to display
     SelectStory(String),
     /// Search query changed
     SearchChanged(String),
+    /// Toggle rendering the story side by side in the current theme and another one
+    ToggleCompare,
+    /// Pick the theme of the second compare pane
+    SetCompareTheme(ThemeInfo),
+    /// Show or hide the theme editor panel
+    ToggleEditor,
+    /// Message from the theme editor panel
+    Editor(EditorMessage),
+    /// Message from the canvas controls of the toolbar
+    Canvas(CanvasMessage),
+    /// A consumer toolbar item changed
+    SetToolbarValue(&'static str, ToolbarValue),
+    /// Reset the canvas and toolbar items to their defaults
+    ResetToolbar,
+    /// Copy a link to the story as shown to the clipboard (native: the launch
+    /// arguments that reopen it)
+    CopyLink,
+    /// Enter or leave focus mode (`F`)
+    ToggleFocus,
+    /// Leave focus mode (`Esc`, or the browser left fullscreen)
+    ExitFocus,
+    /// Message from the virtual gamepad (controller panel or mapped keys)
+    Gamepad(GamepadMessage),
+    /// Gamepad input (from the virtual gamepad, a device or a test): navigates the
+    /// sidebar while it holds the focus, and goes to stories otherwise
+    GamepadInput(GamepadEvent),
+    /// Show or hide the performance HUD (`P`)
+    TogglePerf,
+    /// Frame drawn while the performance HUD is shown
+    PerfFrame(Instant),
+    /// Message from a specific story (e.g. one shown in a pop-out window)
+    StoryFor(String, M),
+    /// Pop the selected story out into its own window (native only)
+    PopOut,
+    /// Set the theme of a pop-out window
+    SetWindowTheme(window::Id, ThemeInfo),
+    /// A window was closed
+    WindowClosed(window::Id),
 }
 
 impl<S> Storybook<S>
@@ -49,50 +174,201 @@ where
 {
     /// Create a new Storybook with the given registry
     pub fn new() -> (Self, Task<Message<S::Message>>) {
+        Self::with_settings(Settings::default())
+    }
+
+    /// Create a new Storybook with the given registry and settings
+    pub fn with_settings(settings: Settings) -> (Self, Task<Message<S::Message>>) {
+        Self::with_fonts(
+            settings,
+            FontRegistry::new(settings.fonts),
+            Startup::detect(),
+        )
+    }
+
+    /// Create a new Storybook with the fonts of `settings` already parsed, starting
+    /// from the given theme, route and toolbar values
+    pub(crate) fn with_fonts(
+        settings: Settings,
+        fonts: FontRegistry,
+        startup: Startup,
+    ) -> (Self, Task<Message<S::Message>>) {
         let stories = S::default();
-        let preferences = Preferences::load();
-        let brightness = preferences.brightness();
+        let preferences = Preferences::new(startup.theme_mode.unwrap_or_default());
+        let system_brightness = preferences::system_brightness();
+
+        let themes = S::Provider::themes();
+        if let ThemeMode::Named(id) = preferences.theme_mode() {
+            if !themes.iter().any(|theme| theme.id == id) {
+                tracing::warn!("Unknown theme \"{}\", falling back to a default theme", id);
+            }
+        }
+        let theme = preferences.theme_mode().resolve(&themes, system_brightness);
 
         // Build sidebar config from story metadata
-        let story_list = S::stories();
-        let sidebar_config = build_sidebar_config(S::title(), &story_list);
+        let story_list = story_list::<S>();
+        let sidebar_config = build_sidebar_config(S::title(), &story_list, themes);
+
+        // Check URL hash for initial story (following aliases), otherwise use first story
+        // or empty for welcome. Unknown routes show the not-found page.
+        let mut not_found = None;
+        let selected = match startup.route {
+            Some(route) if route == S::welcome_id() => String::new(),
+            Some(route) => match routing::resolve_route(&route, &story_list) {
+                Some(id) => id.to_string(),
+                None => {
+                    not_found = Some(NotFound::new(route, &story_list));
+                    String::new()
+                }
+            },
+            None => story_list
+                .first()
+                .map(|s| s.id.to_string())
+                .unwrap_or_default(),
+        };
 
-        // Check URL hash for initial story, otherwise use first story or empty for welcome
-        let selected = routing::get_initial_route()
-            .filter(|id| story_list.iter().any(|s| s.id == id))
-            .or_else(|| story_list.first().map(|s| s.id.to_string()))
-            .unwrap_or_default();
+        // Sync URL to the canonical story id (in case we followed an alias or defaulted
+        // to first), but keep an unknown route visible in the address bar
+        if not_found.is_none() {
+            routing::set_url_hash(&selected);
+        }
+
+        let mut canvas = StoryCanvas::default();
+        canvas.set_story_background(story_background(&story_list, &selected));
 
-        // Sync URL to selected story (in case we defaulted to first)
-        routing::set_url_hash(&selected);
+        // Toolbar values from the URL, if any
+        let toolbar_items = S::toolbar();
+        let toolbar_values = match startup.toolbar {
+            Some(query) => ToolbarValues::from_query(&toolbar_items, &query),
+            None => ToolbarValues::defaults(&toolbar_items),
+        };
 
         let app = Self {
             stories,
             selected,
-            brightness,
+            not_found,
+            theme,
+            system_brightness,
+            transition: None,
             preferences,
             sidebar_config,
             search_query: String::new(),
+            embed: settings.embed || routing::embed_requested(),
+            compare: false,
+            compare_theme: None,
+            focus: false,
+            gamepad: VirtualGamepad::default(),
+            gamepad_events: GamepadChannel::new(),
+            gamepad_source: settings.gamepad,
+            sidebar_focus: None,
+            perf: PerfMonitor::default(),
+            design_tokens: generates_design_tokens::<S>(),
+            fonts_story: generates_fonts::<S>(),
+            fonts,
+            font_samples: settings.font_samples,
+            font_coverage: OnceCell::new(),
+            editor: ThemeEditor::default(),
+            canvas,
+            viewports: settings.viewports,
+            toolbar_items,
+            toolbar_values,
+            title: settings.title,
+            main_window: None,
+            windows: BTreeMap::new(),
         };
 
-        (app, Task::none())
+        let task = preferences::detect_system_brightness().map(Message::InitialBrightness);
+
+        (app, task)
+    }
+
+    /// Create a new Storybook that opens its own main window
+    ///
+    /// Used when running as a multi-window daemon, which starts without any window.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_main_window(settings: Settings) -> (Self, Task<Message<S::Message>>) {
+        Self::with_main_window_and_fonts(
+            settings,
+            FontRegistry::new(settings.fonts),
+            Startup::detect(),
+        )
+    }
+
+    /// Create a new Storybook that opens its own main window, with the fonts of
+    /// `settings` already parsed, starting from the given theme, route and toolbar
+    /// values
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn with_main_window_and_fonts(
+        settings: Settings,
+        fonts: FontRegistry,
+        startup: Startup,
+    ) -> (Self, Task<Message<S::Message>>) {
+        let (mut app, task) = Self::with_fonts(settings, fonts, startup);
+
+        let (id, open) = window::open(window::Settings {
+            size: settings.window_size,
+            ..Default::default()
+        });
+        app.main_window = Some(id);
+
+        (app, Task::batch([open.discard(), task]))
     }
 
     /// Update the application state
     pub fn update(&mut self, message: Message<S::Message>) -> Task<Message<S::Message>> {
         match message {
             Message::Story(msg) => {
-                self.stories.update(&self.selected, msg);
+                let stories = &mut self.stories;
+                let selected = &self.selected;
+                self.perf.time_update(|| stories.update(selected, msg));
                 Task::none()
             }
             Message::ToggleBrightness => {
-                self.brightness = self.brightness.toggle();
-                self.preferences.set_brightness(self.brightness);
-                self.preferences.save();
+                let brightness = self.theme.brightness.toggle();
+                let theme = first_theme_with(&self.sidebar_config.themes, brightness);
+                self.set_theme_mode(ThemeMode::Named(theme.id.to_string()));
+                Task::none()
+            }
+            Message::SetThemeMode(theme_mode) => {
+                self.set_theme_mode(theme_mode);
+                Task::none()
+            }
+            Message::InitialBrightness(brightness) => {
+                self.system_brightness = brightness;
+                self.theme = self
+                    .preferences
+                    .theme_mode()
+                    .resolve(&self.sidebar_config.themes, brightness);
+                self.transition = None;
+                Task::none()
+            }
+            Message::SystemBrightnessChanged(brightness) => {
+                self.system_brightness = brightness;
+                let theme = self
+                    .preferences
+                    .theme_mode()
+                    .resolve(&self.sidebar_config.themes, brightness);
+                self.switch_theme(theme);
+                Task::none()
+            }
+            Message::TransitionFrame(now) => {
+                let to_colors = SidebarColors::from_theme(self.sidebar_theme(&self.theme));
+                let finished = self.transition.as_mut().is_none_or(|transition| {
+                    transition.advance(now, to_colors);
+                    transition.is_finished()
+                });
+                if finished {
+                    self.transition = None;
+                }
                 Task::none()
             }
             Message::SelectStory(id) => {
+                self.not_found = None;
                 self.selected = id.clone();
+                self.perf.reset();
+                self.canvas.reset_pan();
+                self.canvas
+                    .set_story_background(story_background(&story_list::<S>(), &id));
                 routing::set_url_hash(&id);
                 Task::none()
             }
@@ -100,18 +376,606 @@ where
                 self.search_query = query;
                 Task::none()
             }
+            Message::ToggleCompare => {
+                self.compare = !self.compare && self.compare_themes().is_some();
+                Task::none()
+            }
+            Message::SetCompareTheme(theme) => {
+                self.compare_theme = Some(theme);
+                Task::none()
+            }
+            Message::ToggleEditor => {
+                self.editor.open = !self.editor.open;
+                Task::none()
+            }
+            Message::Editor(msg) => {
+                let theme = self.theme;
+                self.editor.update(msg, &theme, || {
+                    ThemeEdits::from_provider::<S::Provider>(&theme)
+                })
+            }
+            Message::Canvas(msg) => {
+                self.canvas.update(msg);
+                Task::none()
+            }
+            Message::SetToolbarValue(id, value) => {
+                self.toolbar_values.set(id, value);
+                routing::set_url_toolbar(
+                    self.toolbar_values.to_query(&self.toolbar_items).as_deref(),
+                );
+                Task::none()
+            }
+            Message::ResetToolbar => {
+                self.canvas.reset();
+                self.toolbar_values = ToolbarValues::defaults(&self.toolbar_items);
+                routing::set_url_toolbar(None);
+                Task::none()
+            }
+            Message::CopyLink => iced::clipboard::write(routing::share_link(
+                &self.selected,
+                self.preferences.theme_mode(),
+                self.toolbar_values.to_query(&self.toolbar_items).as_deref(),
+            )),
+            Message::ToggleFocus => {
+                self.focus = !self.focus;
+                if self.focus {
+                    // The sidebar is hidden, so stories get the gamepad back
+                    self.sidebar_focus = None;
+                }
+                fullscreen::set_fullscreen(self.main_window, self.focus)
+            }
+            Message::ExitFocus if self.focus => {
+                self.focus = false;
+                fullscreen::set_fullscreen(self.main_window, false)
+            }
+            Message::ExitFocus => Task::none(),
+            Message::Gamepad(msg) => {
+                let events = self.gamepad.update(msg);
+                Task::batch(events.into_iter().map(|event| self.gamepad_input(event)))
+            }
+            Message::GamepadInput(event) => self.gamepad_input(event),
+            Message::TogglePerf => {
+                self.perf.toggle();
+                Task::none()
+            }
+            Message::PerfFrame(now) => {
+                self.perf.frame(now);
+                Task::none()
+            }
+            Message::StoryFor(story_id, msg) => {
+                self.stories.update(&story_id, msg);
+                Task::none()
+            }
+            Message::PopOut => self.pop_out(),
+            Message::SetWindowTheme(id, theme) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.theme = theme;
+                }
+                Task::none()
+            }
+            Message::WindowClosed(id) => {
+                self.windows.remove(&id);
+                if self.main_window == Some(id) {
+                    iced::exit()
+                } else {
+                    Task::none()
+                }
+            }
+        }
+    }
+
+    /// Open the selected story in a new window
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pop_out(&mut self) -> Task<Message<S::Message>> {
+        if self.main_window.is_none() || self.selected.is_empty() {
+            return Task::none();
+        }
+
+        let title = story_list::<S>()
+            .into_iter()
+            .find(|story| story.id == self.selected)
+            .map(|story| story.title.to_string())
+            .unwrap_or_else(|| self.selected.clone());
+
+        let (id, open) = window::open(window::Settings {
+            size: crate::windows::POP_OUT_WINDOW_SIZE,
+            ..Default::default()
+        });
+        self.windows.insert(
+            id,
+            StoryWindow {
+                story_id: self.selected.clone(),
+                title,
+                theme: self.theme,
+            },
+        );
+
+        open.discard()
+    }
+
+    /// Pop-out windows are not supported on WASM
+    #[cfg(target_arch = "wasm32")]
+    fn pop_out(&mut self) -> Task<Message<S::Message>> {
+        Task::none()
+    }
+
+    /// Sidebar control holding the gamepad focus, if the sidebar has it
+    pub fn sidebar_focus(&self) -> Option<&SidebarFocus> {
+        self.sidebar_focus.as_ref()
+    }
+
+    /// Route gamepad input to the sidebar or to stories
+    ///
+    /// Menu moves the input to the sidebar (focusing the selected story). There
+    /// the D-pad moves the focus up and down (the toolbar's theme picker sits
+    /// above the sidebar controls), A activates the focused control (picking the
+    /// next theme, or opening a story, which returns the input to it), and B,
+    /// Right or Menu return to the story. Releases and stick moves always reach
+    /// stories, so buttons and sticks held when switching don't stay down or
+    /// deflected.
+    fn gamepad_input(&mut self, event: GamepadEvent) -> Task<Message<S::Message>> {
+        let Some(focused) = self.sidebar_focus.clone() else {
+            return match event {
+                GamepadEvent::ButtonPressed(GamepadButton::Menu) if !self.embed && !self.focus => {
+                    let order = focus_order(
+                        &self.sidebar_config,
+                        &self.search_query,
+                        self.sidebar_controls(),
+                    );
+                    let selected = SidebarFocus::Story(self.selected.clone());
+                    let target = if order.contains(&selected) {
+                        selected
+                    } else {
+                        order[0].clone()
+                    };
+                    self.focus_sidebar(target, &order)
+                }
+                GamepadEvent::ButtonPressed(GamepadButton::Menu)
+                | GamepadEvent::ButtonReleased(GamepadButton::Menu) => Task::none(),
+                event => {
+                    self.gamepad_events.send(event);
+                    Task::none()
+                }
+            };
+        };
+
+        match event {
+            GamepadEvent::ButtonPressed(GamepadButton::DPad(
+                direction @ (Direction::Up | Direction::Down),
+            )) => {
+                let order = focus_order(
+                    &self.sidebar_config,
+                    &self.search_query,
+                    self.sidebar_controls(),
+                );
+                let target = match order.iter().position(|control| *control == focused) {
+                    Some(index) if direction == Direction::Up => {
+                        order[index.saturating_sub(1)].clone()
+                    }
+                    Some(index) => order[(index + 1).min(order.len() - 1)].clone(),
+                    None => order[0].clone(),
+                };
+                self.focus_sidebar(target, &order)
+            }
+            GamepadEvent::ButtonPressed(GamepadButton::A) => match focused {
+                SidebarFocus::Theme => self.update(Message::SetThemeMode(self.next_theme_mode())),
+                SidebarFocus::Compare => self.update(Message::ToggleCompare),
+                SidebarFocus::Editor => self.update(Message::ToggleEditor),
+                SidebarFocus::PopOut => self.update(Message::PopOut),
+                SidebarFocus::Search => iced::widget::operation::focus(SEARCH_INPUT_ID),
+                SidebarFocus::Story(id) => {
+                    self.sidebar_focus = None;
+                    Task::batch([self.update(Message::SelectStory(id)), unfocus_inputs()])
+                }
+            },
+            GamepadEvent::ButtonPressed(
+                GamepadButton::B | GamepadButton::Menu | GamepadButton::DPad(Direction::Right),
+            ) => {
+                self.sidebar_focus = None;
+                unfocus_inputs()
+            }
+            GamepadEvent::ButtonReleased(button) if button != GamepadButton::Menu => {
+                self.gamepad_events.send(event);
+                Task::none()
+            }
+            GamepadEvent::StickMoved { .. } => {
+                self.gamepad_events.send(event);
+                Task::none()
+            }
+            _ => Task::none(),
         }
     }
 
+    /// Theme mode after the current one in the toolbar's theme picker ("System",
+    /// then the named themes), wrapping around
+    fn next_theme_mode(&self) -> ThemeMode {
+        let modes: Vec<ThemeMode> = std::iter::once(ThemeMode::System)
+            .chain(
+                self.sidebar_config
+                    .themes
+                    .iter()
+                    .map(|theme| ThemeMode::Named(theme.id.to_string())),
+            )
+            .collect();
+        let current = self.preferences.theme_mode();
+
+        match modes.iter().position(|mode| mode == current) {
+            Some(index) => modes[(index + 1) % modes.len()].clone(),
+            None => ThemeMode::System,
+        }
+    }
+
+    /// Give a sidebar control the gamepad focus, scrolling the navigation list
+    /// towards it
+    fn focus_sidebar(
+        &mut self,
+        target: SidebarFocus,
+        order: &[SidebarFocus],
+    ) -> Task<Message<S::Message>> {
+        // The search input keeps the keyboard only while it has the gamepad focus
+        let unfocus = if target == SidebarFocus::Search {
+            Task::none()
+        } else {
+            unfocus_inputs()
+        };
+
+        let stories: Vec<&SidebarFocus> = order
+            .iter()
+            .filter(|control| matches!(control, SidebarFocus::Story(_)))
+            .collect();
+        let scroll = match stories.iter().position(|control| **control == target) {
+            Some(index) => iced::widget::operation::snap_to(
+                NAV_LIST_ID,
+                iced::widget::operation::RelativeOffset {
+                    x: 0.0,
+                    y: index as f32 / (stories.len() - 1).max(1) as f32,
+                },
+            ),
+            None => Task::none(),
+        };
+
+        self.sidebar_focus = Some(target);
+        Task::batch([unfocus, scroll])
+    }
+
     /// Render the application view
     pub fn view(&self) -> Element<'_, Message<S::Message>> {
         // Get themes from the consumer's provider
-        let theme = S::Provider::get_theme(self.brightness);
-        let sidebar_theme = S::Provider::get_sidebar_theme(self.brightness);
+        let theme = S::Provider::get_named_theme(&self.theme);
+        let sidebar_theme = self.chrome_sidebar_theme();
 
-        // Check if consumer provides a custom sidebar
-        let sidebar_view = self
-            .stories
+        // Render main content area (unpadded when embedded)
+        let padding = if self.embed { 0 } else { 32 };
+        let is_story = self.not_found.is_none() && !self.selected.is_empty();
+        let compare_themes = self.compare_themes().filter(|_| self.compare && is_story);
+        let content_area: Element<'_, Message<S::Message>> = if let Some(themes) = compare_themes {
+            self.compare_view(themes, padding)
+        } else {
+            let content = if let Some(not_found) = &self.not_found {
+                not_found_view(not_found, sidebar_theme)
+            } else if self.selected.is_empty() {
+                self.stories.welcome_view(theme).map(Message::Story)
+            } else {
+                self.perf
+                    .time_view(|| {
+                        self.story_view(&self.selected, &self.theme, self.story_context())
+                    })
+                    .map(Message::Story)
+            };
+
+            // Stories render in a fixed-size frame when a viewport is picked
+            match self.canvas.frame_size().filter(|_| is_story) {
+                Some(size) => self.canvas_view(framed(content, size), true, padding, sidebar_theme),
+                None => self.canvas_view(content, false, padding, sidebar_theme),
+            }
+        };
+
+        // Performance HUD in the bottom-right corner of the canvas
+        let content_area = if self.perf.is_enabled() && is_story {
+            stack![
+                content_area,
+                container(perf_hud(&self.perf, sidebar_theme))
+                    .padding(12)
+                    .align_right(Length::Fill)
+                    .align_bottom(Length::Fill),
+            ]
+            .into()
+        } else {
+            content_area
+        };
+
+        // Layout: sidebar | toolbar over content | editor, or the story alone when
+        // embedded or in focus mode
+        let layout: Element<'_, Message<S::Message>> = if self.embed || self.focus {
+            content_area
+        } else {
+            let mut content_area = column![self.toolbar_view(sidebar_theme), content_area];
+            if self.gamepad.is_enabled() {
+                content_area = content_area
+                    .push(gamepad_panel(&self.gamepad, sidebar_theme).map(Message::Gamepad));
+            }
+            self.chrome_layout(theme, sidebar_theme, content_area.into())
+        };
+        let bg_color = sidebar_theme.content_background();
+
+        container(layout)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(bg_color)),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Sidebar, content and (when open) the editor panel, side by side
+    fn chrome_layout<'a>(
+        &'a self,
+        theme: &'static <S::Provider as ThemeProvider>::Theme,
+        sidebar_theme: &'a dyn SidebarTheme,
+        content_area: Element<'a, Message<S::Message>>,
+    ) -> Element<'a, Message<S::Message>> {
+        if self.editor.open {
+            row![
+                self.sidebar_view(theme, sidebar_theme),
+                content_area,
+                self.editor_panel(sidebar_theme)
+            ]
+            .into()
+        } else {
+            row![self.sidebar_view(theme, sidebar_theme), content_area].into()
+        }
+    }
+
+    /// Toolbar above the canvas
+    fn toolbar_view<'a>(
+        &'a self,
+        sidebar_theme: &'a dyn SidebarTheme,
+    ) -> Element<'a, Message<S::Message>> {
+        toolbar(
+            &self.canvas,
+            self.viewports,
+            &self.sidebar_config.themes,
+            ToolbarControls {
+                theme_mode: self.preferences.theme_mode(),
+                theme_focused: self.sidebar_focus == Some(SidebarFocus::Theme),
+                gamepad: self.gamepad.is_enabled(),
+                perf: self.perf.is_enabled(),
+            },
+            &self.toolbar_items,
+            &self.toolbar_values,
+            sidebar_theme,
+        )
+        .map(|msg| match msg {
+            ToolbarMessage::SetThemeMode(mode) => Message::SetThemeMode(mode),
+            ToolbarMessage::Canvas(msg) => Message::Canvas(msg),
+            ToolbarMessage::Set(id, value) => Message::SetToolbarValue(id, value),
+            ToolbarMessage::Reset => Message::ResetToolbar,
+            ToolbarMessage::CopyLink => Message::CopyLink,
+            ToolbarMessage::ToggleFocus => Message::ToggleFocus,
+            ToolbarMessage::ToggleGamepad => Message::Gamepad(GamepadMessage::Toggle),
+            ToolbarMessage::TogglePerf => Message::TogglePerf,
+        })
+    }
+
+    /// Zoomable, pannable canvas around content
+    fn canvas_view<'a>(
+        &self,
+        content: Element<'a, Message<S::Message>>,
+        framed: bool,
+        padding: u16,
+        sidebar_theme: &dyn SidebarTheme,
+    ) -> Element<'a, Message<S::Message>> {
+        Stage::new(
+            content,
+            self.canvas.zoom(),
+            self.canvas.pan(),
+            Message::Canvas,
+        )
+        .padding(f32::from(padding))
+        .framed(framed)
+        .background(self.canvas.background(), sidebar_theme.content_background())
+        .layout_overlay(self.canvas.layout_overlay())
+        .rulers(self.canvas.rulers())
+        .grid(self.canvas.grid().spacing())
+        .measure(self.canvas.measure())
+        .guide_colors(GuideColors::from_theme(sidebar_theme))
+        .into()
+    }
+
+    /// Themes of the two compare panes: the current theme and the picked one
+    ///
+    /// Without a pick (or when it became the current theme), the second pane
+    /// shows the first theme of the opposite brightness, else the first other
+    /// theme. `None` when the provider has fewer than two themes.
+    fn compare_themes(&self) -> Option<(ThemeInfo, ThemeInfo)> {
+        let themes = &self.sidebar_config.themes;
+        let others = || themes.iter().filter(|theme| **theme != self.theme);
+
+        let other = self
+            .compare_theme
+            .filter(|picked| *picked != self.theme && themes.contains(picked))
+            .or_else(|| {
+                others()
+                    .find(|theme| theme.brightness != self.theme.brightness)
+                    .copied()
+            })
+            .or_else(|| others().next().copied())?;
+
+        Some((self.theme, other))
+    }
+
+    /// Render the selected story twice, side by side in two themes; the second
+    /// pane's theme is picked above it
+    fn compare_view(
+        &self,
+        (current, other): (ThemeInfo, ThemeInfo),
+        padding: u16,
+    ) -> Element<'_, Message<S::Message>> {
+        let panes = [current, other].map(|info| {
+            let sidebar_theme = self.sidebar_theme(&info);
+
+            let frame_size = self.canvas.frame_size();
+            // Only the current theme's pane follows the chrome's transition and
+            // is timed by the performance HUD
+            let story = if info == current {
+                self.perf
+                    .time_view(|| self.story_view(&self.selected, &info, self.story_context()))
+            } else {
+                self.story_view(&self.selected, &info, StoryContext::new())
+            }
+            .map(Message::Story);
+            let story = match frame_size {
+                Some(size) => framed(story, size),
+                None => story,
+            };
+
+            // The label stays outside the stage, so rulers start at the story
+            let label: Element<'_, Message<S::Message>> = if info == current {
+                text(info.label)
+                    .size(sidebar_theme.section_size())
+                    .color(sidebar_theme.text_secondary())
+                    .into()
+            } else {
+                let button_font = sidebar_theme.button_font();
+                let choices: Vec<ThemeInfo> = self
+                    .sidebar_config
+                    .themes
+                    .iter()
+                    .filter(|theme| **theme != current)
+                    .copied()
+                    .collect();
+
+                pick_list(choices, Some(info), Message::SetCompareTheme)
+                    .text_size(sidebar_theme.button_size())
+                    .font(button_font.font)
+                    .text_shaping(button_font.shaping)
+                    .padding([4, 8])
+                    .style(move |_, status| sidebar_theme.pick_list_style(status))
+                    .menu_style(move |_| sidebar_theme.menu_style())
+                    .into()
+            };
+            let pane = column![
+                container(label).padding(iced::Padding::new(f32::from(padding)).bottom(0)),
+                self.canvas_view(story, frame_size.is_some(), padding, sidebar_theme),
+            ];
+
+            // Each pane styles built-in widgets with its own palette
+            container(themer(Some(self.iced_theme(&info)), pane))
+                .width(Length::FillPortion(1))
+                .height(Length::Fill)
+                .into()
+        });
+
+        Row::with_children(panes).into()
+    }
+
+    /// Context of the main window's story views
+    fn story_context(&self) -> StoryContext {
+        StoryContext::with_theme_transition(self.transition.as_ref().map(ThemeTransition::state))
+    }
+
+    /// Render a story with the given theme, including icebook's generated stories
+    fn story_view(
+        &self,
+        story_id: &str,
+        info: &ThemeInfo,
+        context: StoryContext,
+    ) -> Element<'_, S::Message> {
+        match story_id {
+            DESIGN_TOKENS_STORY_ID if self.design_tokens => {
+                let tokens = match self.editor.edits(info) {
+                    Some(edits) => edits.tokens.clone(),
+                    None => S::Provider::design_tokens(info),
+                };
+                design_tokens_view(tokens, self.sidebar_theme(info))
+            }
+            FONTS_STORY_ID if self.fonts_story => {
+                let coverage = self
+                    .font_coverage
+                    .get_or_init(|| FontCoverage::new(&self.fonts, self.font_samples));
+                fonts_view(&self.fonts, coverage, self.sidebar_theme(info))
+            }
+            _ => self.stories.view_with_context(
+                story_id,
+                S::Provider::get_named_theme(info),
+                &self.toolbar_values,
+                &context
+                    .with_edited_tokens(self.editor.edits(info).map(|edits| edits.tokens.clone())),
+            ),
+        }
+    }
+
+    /// Render the view of a specific window (multi-window daemon)
+    pub fn view_window(&self, id: window::Id) -> Element<'_, Message<S::Message>> {
+        let Some(window) = self.windows.get(&id) else {
+            return self.view();
+        };
+
+        let sidebar_theme = self.sidebar_theme(&window.theme);
+        let story_id = window.story_id.clone();
+        let content = self
+            .story_view(&window.story_id, &window.theme, StoryContext::new())
+            .map(move |msg| Message::StoryFor(story_id.clone(), msg));
+
+        story_window_view(
+            window,
+            content,
+            &self.sidebar_config.themes,
+            move |theme| Message::SetWindowTheme(id, theme),
+            sidebar_theme,
+        )
+    }
+
+    /// Render the theme editor panel for the current theme
+    fn editor_panel<'a>(
+        &'a self,
+        sidebar_theme: &'a dyn SidebarTheme,
+    ) -> Element<'a, Message<S::Message>> {
+        let panel = match self.editor.edits(&self.theme) {
+            Some(edits) => editor_view(&self.editor, &self.theme, edits, sidebar_theme),
+            None => editor_view(
+                &self.editor,
+                &self.theme,
+                &ThemeEdits::from_provider::<S::Provider>(&self.theme),
+                sidebar_theme,
+            ),
+        };
+
+        panel.map(Message::Editor)
+    }
+
+    /// Sidebar theme of a named theme, with live edits applied
+    fn sidebar_theme(&self, info: &ThemeInfo) -> &dyn SidebarTheme {
+        match self.editor.edits(info) {
+            Some(edits) => &edits.sidebar,
+            None => S::Provider::get_named_sidebar_theme(info),
+        }
+    }
+
+    /// Sidebar theme of the main window's chrome, blended during a theme transition
+    fn chrome_sidebar_theme(&self) -> &dyn SidebarTheme {
+        match &self.transition {
+            Some(transition) => &transition.sidebar,
+            None => self.sidebar_theme(&self.theme),
+        }
+    }
+
+    /// Iced theme of a named theme, with live edits applied
+    fn iced_theme(&self, info: &ThemeInfo) -> iced::Theme {
+        match self.editor.edits(info) {
+            Some(edits) => edits.iced_theme(info),
+            None => S::Provider::get_iced_theme(info),
+        }
+    }
+
+    /// Render the consumer's custom sidebar, or the default one
+    fn sidebar_view<'a>(
+        &'a self,
+        theme: &'static <S::Provider as ThemeProvider>::Theme,
+        sidebar_theme: &'a dyn SidebarTheme,
+    ) -> Element<'a, Message<S::Message>> {
+        self.stories
             .sidebar_view(
                 &self.sidebar_config,
                 &self.selected,
@@ -124,51 +988,43 @@ where
                     &self.sidebar_config,
                     &self.selected,
                     &self.search_query,
+                    self.sidebar_controls(),
                     sidebar_theme,
                 )
             })
             .map(|msg| match msg {
+                // Still emitted by custom sidebars
+                #[allow(deprecated)]
                 SidebarMessage::ToggleBrightness => Message::ToggleBrightness,
                 SidebarMessage::SelectStory(id) => Message::SelectStory(id),
                 SidebarMessage::SearchChanged(query) => Message::SearchChanged(query),
-            });
-
-        // Render main content area
-        let content = if self.selected.is_empty() {
-            self.stories.welcome_view(theme).map(Message::Story)
-        } else {
-            self.stories.view(&self.selected, theme).map(Message::Story)
-        };
-
-        // Wrap content in scrollable area
-        let content_scrollable = scrollable(
-            container(content)
-                .padding(32)
-                .width(Length::Fill)
-                .height(Length::Shrink),
-        )
-        .width(Length::Fill)
-        .height(Length::Fill);
-
-        // Layout: sidebar | content
-        let layout = row![sidebar_view, content_scrollable];
-        let bg_color = sidebar_theme.content_background();
-
-        container(layout)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .style(move |_| container::Style {
-                background: Some(iced::Background::Color(bg_color)),
-                ..Default::default()
+                SidebarMessage::PopOut => Message::PopOut,
+                SidebarMessage::ToggleCompare => Message::ToggleCompare,
+                SidebarMessage::ToggleEditor => Message::ToggleEditor,
             })
-            .into()
+    }
+
+    /// State of the chrome controls shown in the default sidebar
+    fn sidebar_controls(&self) -> SidebarControls<'_> {
+        SidebarControls {
+            can_pop_out: self.main_window.is_some() && !self.selected.is_empty(),
+            can_compare: self.compare_themes().is_some(),
+            compare: self.compare,
+            editing: self.editor.open,
+            focused: self.sidebar_focus.as_ref(),
+        }
     }
 
     /// Get the Iced theme
     pub fn theme(&self) -> iced::Theme {
-        match self.brightness {
-            Brightness::Dark => iced::Theme::Dark,
-            Brightness::Light => iced::Theme::Light,
+        self.iced_theme(&self.theme)
+    }
+
+    /// Get the Iced theme of a specific window (multi-window daemon)
+    pub fn theme_window(&self, id: window::Id) -> iced::Theme {
+        match self.windows.get(&id) {
+            Some(window) => self.iced_theme(&window.theme),
+            None => self.theme(),
         }
     }
 
@@ -178,11 +1034,151 @@ where
         S::title().to_string()
     }
 
+    /// Title of a specific window (multi-window daemon)
+    pub fn title_window(&self, id: window::Id) -> String {
+        match self.windows.get(&id) {
+            Some(window) => format!("{} - {}", window.title, self.title),
+            None => self.title.to_string(),
+        }
+    }
+
     /// Window subscription
     pub fn subscription(&self) -> Subscription<Message<S::Message>> {
-        self.stories
-            .subscription(&self.selected)
-            .map(Message::Story)
+        let story = self
+            .stories
+            .subscription_with_context(
+                &self.selected,
+                &StoryContext::with_gamepad(self.gamepad_events.clone()),
+            )
+            .map(Message::Story);
+
+        // Pop-out windows showing other stories need their subscriptions too
+        let mut pop_out_ids: Vec<&str> = self
+            .windows
+            .values()
+            .map(|window| window.story_id.as_str())
+            .filter(|id| *id != self.selected)
+            .collect();
+        pop_out_ids.sort_unstable();
+        pop_out_ids.dedup();
+        // (without gamepad events, which belong to the selected story)
+        let pop_outs = pop_out_ids.into_iter().map(|id| {
+            self.stories
+                .subscription_with_context(id, &StoryContext::new())
+                .with(id.to_string())
+                .map(|(id, msg)| Message::StoryFor(id, msg))
+        });
+
+        // Track closed windows when running as a multi-window daemon
+        let window_events = if self.main_window.is_some() {
+            window::close_events().map(Message::WindowClosed)
+        } else {
+            Subscription::none()
+        };
+
+        // Animation frames while the chrome colors are transitioning
+        let transition = if self.transition.is_some() {
+            window::frames().map(Message::TransitionFrame)
+        } else {
+            Subscription::none()
+        };
+
+        // Only listen for system theme changes while following the system
+        let system_theme = match self.preferences.theme_mode() {
+            ThemeMode::System => {
+                preferences::system_brightness_changes().map(Message::SystemBrightnessChanged)
+            }
+            _ => Subscription::none(),
+        };
+
+        // Keyboard shortcuts (key presses not handled by a widget, e.g. a focused input)
+        // (mapped to the virtual gamepad first while it is on)
+        let shortcuts = if self.gamepad.is_enabled() {
+            keyboard::listen().filter_map(gamepad_shortcut)
+        } else {
+            keyboard::listen().filter_map(shortcut)
+        };
+
+        // Every frame while the performance HUD is shown
+        let perf_frames = if self.perf.is_enabled() {
+            window::frames().map(Message::PerfFrame)
+        } else {
+            Subscription::none()
+        };
+
+        // Gamepad input from a device
+        let device_gamepad = match self.gamepad_source {
+            Some(source) => source().map(Message::GamepadInput),
+            None => Subscription::none(),
+        };
+
+        // The browser leaves fullscreen on its own with Esc
+        let fullscreen_exits = if self.focus {
+            fullscreen::fullscreen_exits().map(|()| Message::ExitFocus)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch(
+            [
+                story,
+                system_theme,
+                window_events,
+                transition,
+                shortcuts,
+                device_gamepad,
+                perf_frames,
+                fullscreen_exits,
+            ]
+            .into_iter()
+            .chain(pop_outs),
+        )
+    }
+
+    /// Apply and persist a new theme mode
+    fn set_theme_mode(&mut self, theme_mode: ThemeMode) {
+        self.switch_theme(theme_mode.resolve(&self.sidebar_config.themes, self.system_brightness));
+        routing::set_url_theme(match &theme_mode {
+            ThemeMode::System => None,
+            other => Some(other.id()),
+        });
+        self.preferences.set_theme_mode(theme_mode);
+        self.preferences.save();
+    }
+
+    /// Switch to a theme, animating the chrome from the colors shown now
+    ///
+    /// Switches instantly when the provider's transition duration is zero or the
+    /// browser prefers reduced motion.
+    fn switch_theme(&mut self, theme: ThemeInfo) {
+        if theme == self.theme {
+            return;
+        }
+
+        let from = std::mem::replace(&mut self.theme, theme);
+        let from_colors = match &self.transition {
+            Some(transition) => transition.sidebar.colors,
+            None => SidebarColors::from_theme(self.sidebar_theme(&from)),
+        };
+
+        let duration = S::Provider::theme_transition_duration();
+        if duration.is_zero() || preferences::prefers_reduced_motion() {
+            self.transition = None;
+            return;
+        }
+
+        // An edited theme recolors the provider's, keeping its fonts and sizes
+        let target = match self.editor.edits(&theme) {
+            Some(edits) => edits.sidebar.base,
+            None => S::Provider::get_named_sidebar_theme(&theme),
+        };
+        self.transition = Some(ThemeTransition::new(
+            from,
+            from_colors,
+            theme,
+            target,
+            duration,
+        ));
     }
 }
 
@@ -195,8 +1191,116 @@ where
     }
 }
 
+/// Message of a keyboard shortcut
+///
+/// - `L`: toggle widget outlines
+/// - `Shift+L`: toggle padding and spacing
+/// - `R`: toggle rulers
+/// - `M`: toggle the measure tool
+/// - `F`: toggle focus mode
+/// - `P`: toggle the performance HUD
+/// - `Esc`: leave focus mode
+fn shortcut<M>(event: keyboard::Event) -> Option<Message<M>> {
+    let keyboard::Event::KeyPressed {
+        key,
+        modifiers,
+        repeat: false,
+        ..
+    } = event
+    else {
+        return None;
+    };
+    if modifiers.command() || modifiers.alt() {
+        return None;
+    }
+
+    let key = match key {
+        keyboard::Key::Named(keyboard::key::Named::Escape) => return Some(Message::ExitFocus),
+        keyboard::Key::Character(key) => key,
+        _ => return None,
+    };
+
+    match key.to_lowercase().as_str() {
+        "l" if modifiers.shift() => Some(Message::Canvas(CanvasMessage::ToggleSpacing)),
+        "l" => Some(Message::Canvas(CanvasMessage::ToggleOutlines)),
+        "r" => Some(Message::Canvas(CanvasMessage::ToggleRulers)),
+        "m" => Some(Message::Canvas(CanvasMessage::ToggleMeasure)),
+        "f" => Some(Message::ToggleFocus),
+        "p" => Some(Message::TogglePerf),
+        _ => None,
+    }
+}
+
+/// Message of a key mapped to the virtual gamepad, else of a keyboard shortcut
+fn gamepad_shortcut<M>(event: keyboard::Event) -> Option<Message<M>> {
+    match &event {
+        keyboard::Event::KeyPressed {
+            key,
+            modifiers,
+            repeat,
+            ..
+        } if !modifiers.command() && !modifiers.alt() => {
+            if let Some(input) = key_input(key) {
+                return (!repeat).then_some(Message::Gamepad(GamepadMessage::Press(input)));
+            }
+        }
+        keyboard::Event::KeyReleased { key, .. } => {
+            return key_input(key).map(|input| Message::Gamepad(GamepadMessage::Release(input)));
+        }
+        _ => {}
+    }
+
+    shortcut(event)
+}
+
+/// Unfocus text inputs (the story search), so mapped keys reach the gamepad again
+fn unfocus_inputs<M: Send + 'static>() -> Task<M> {
+    iced::advanced::widget::operate(iced::advanced::widget::operation::focusable::unfocus::<()>())
+        .discard()
+}
+
+/// Default canvas background of a story
+fn story_background(stories: &[StoryMeta], id: &str) -> Option<CanvasBackground> {
+    stories
+        .iter()
+        .find(|story| story.id == id)
+        .and_then(|story| story.background)
+}
+
+/// All stories: the registry's stories followed by icebook's generated stories
+pub(crate) fn story_list<S: StoryRegistry>() -> Vec<StoryMeta> {
+    let mut stories = S::stories();
+    if generates_design_tokens::<S>() {
+        stories.push(DESIGN_TOKENS_STORY);
+    }
+    if generates_fonts::<S>() {
+        stories.push(FONTS_STORY);
+    }
+    stories
+}
+
+/// Whether to generate the design tokens story (any theme lists tokens, and the
+/// registry has no story with the same id)
+fn generates_design_tokens<S: StoryRegistry>() -> bool {
+    !S::stories()
+        .iter()
+        .any(|story| story.id == DESIGN_TOKENS_STORY_ID)
+        && S::Provider::themes()
+            .iter()
+            .any(|theme| !S::Provider::design_tokens(theme).is_empty())
+}
+
+/// Whether to generate the fonts story (the registry has no story with the same id)
+fn generates_fonts<S: StoryRegistry>() -> bool {
+    !S::stories().iter().any(|story| story.id == FONTS_STORY_ID)
+}
+
 /// Build sidebar configuration from story metadata (owned Strings)
-fn build_sidebar_config(title: &str, stories: &[StoryMeta]) -> SidebarConfig {
+fn build_sidebar_config(
+    title: &str,
+    stories: &[StoryMeta],
+    themes: Vec<ThemeInfo>,
+) -> SidebarConfig {
     // Group stories by category
     let mut categories: std::collections::BTreeMap<String, Vec<NavItem>> =
         std::collections::BTreeMap::new();
@@ -217,9 +1321,75 @@ fn build_sidebar_config(title: &str, stories: &[StoryMeta]) -> SidebarConfig {
     SidebarConfig {
         title: title.to_string(),
         sections,
+        themes,
     }
 }
 
+/// An unknown route together with "did you mean" suggestions
+struct NotFound {
+    route: String,
+    suggestions: Vec<NavItem>,
+}
+
+impl NotFound {
+    fn new(route: String, stories: &[StoryMeta]) -> Self {
+        let suggestions = routing::suggest_stories(&route, stories)
+            .into_iter()
+            .map(|story| NavItem {
+                id: story.id.to_string(),
+                label: story.title.to_string(),
+            })
+            .collect();
+
+        Self { route, suggestions }
+    }
+}
+
+/// Not-found view for routes that match no story id or alias
+fn not_found_view<'a, M: Clone + 'a>(
+    not_found: &'a NotFound,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, Message<M>> {
+    let text_color = theme.text_primary();
+    let text_secondary = theme.text_secondary();
+
+    let mut content = Column::new()
+        .spacing(16)
+        .padding(32)
+        .push(text("Story not found").size(32).color(text_color))
+        .push(
+            text(format!("No story matches \"{}\".", not_found.route))
+                .size(16)
+                .color(text_secondary),
+        );
+
+    if !not_found.suggestions.is_empty() {
+        let suggestions =
+            not_found
+                .suggestions
+                .iter()
+                .fold(Column::new().spacing(4), |suggestions, item| {
+                    suggestions.push(
+                        button(text(format!("{} ({})", item.label, item.id)).size(16))
+                            .on_press(Message::SelectStory(item.id.clone()))
+                            .padding([8, 12])
+                            .style(move |_, status| theme.nav_item_style(status, false)),
+                    )
+                });
+
+        content = content
+            .push(text("Did you mean:").size(16).color(text_secondary))
+            .push(suggestions);
+    }
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
+
 /// Default welcome view when no story is selected
 ///
 /// This provides a simple welcome message that works with any message type.
@@ -258,8 +1428,47 @@ pub struct Settings {
     /// Initial window size
     pub window_size: Size,
     /// Font bytes to load (use `include_bytes!` for WASM)
-    /// Pass multiple fonts for different weights/styles
+    /// Pass multiple fonts for different weights/styles. Fonts that fail to parse
+    /// are skipped with a warning; the rest are listed on the generated "Fonts" page.
     pub fonts: &'static [&'static [u8]],
+    /// Sample strings (language, text) checked for glyph coverage on the "Fonts" page
+    pub font_samples: &'static [(&'static str, &'static str)],
+    /// Viewport presets offered in the toolbar
+    pub viewports: &'static [Viewport],
+    /// Render only the selected story, without the sidebar or padding
+    /// (for embedding in iframes). On WASM, `?embed=1` in the URL also enables this.
+    pub embed: bool,
+    /// Gamepad input from a device (e.g. polled with `gilrs`), used to navigate
+    /// the sidebar and sent to stories like the virtual gamepad's events
+    pub gamepad: Option<fn() -> Subscription<GamepadEvent>>,
+}
+
+/// Initial state from outside the app: the URL and localStorage on WASM, the
+/// launch options on native
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Startup {
+    /// Theme mode to start with
+    pub theme_mode: Option<ThemeMode>,
+    /// Route of the story to open (the first story when `None`)
+    pub route: Option<String>,
+    /// Values of the toolbar items (`id:value` pairs, comma-separated)
+    pub toolbar: Option<String>,
+}
+
+impl Startup {
+    /// Read the initial state of this process or page
+    pub fn detect() -> Self {
+        // A theme in the URL takes precedence over the saved preference
+        let theme_mode = routing::get_url_theme()
+            .map(|theme_id| ThemeMode::from_id(&theme_id))
+            .or_else(preferences::load_theme_mode);
+
+        Self {
+            theme_mode,
+            route: routing::get_initial_route(),
+            toolbar: routing::get_url_toolbar(),
+        }
+    }
 }
 
 impl Default for Settings {
@@ -268,6 +1477,231 @@ impl Default for Settings {
             title: "icebook",
             window_size: Size::new(1200.0, 800.0),
             fonts: &[],
+            font_samples: DEFAULT_FONT_SAMPLES,
+            viewports: DEFAULT_VIEWPORTS,
+            embed: false,
+            gamepad: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamepad::Stick;
+
+    struct Provider;
+
+    impl ThemeProvider for Provider {
+        type Theme = ();
+
+        fn get_theme(_brightness: Brightness) -> &'static Self::Theme {
+            &()
+        }
+    }
+
+    #[derive(Default)]
+    struct Stories;
+
+    impl StoryRegistry for Stories {
+        type Message = ();
+        type Provider = Provider;
+
+        fn stories() -> Vec<StoryMeta> {
+            vec![
+                StoryMeta::new("button", "Button", "Inputs"),
+                StoryMeta::new("slider", "Slider", "Inputs"),
+                StoryMeta::new("card", "Card", "Layout"),
+            ]
+        }
+
+        fn update(&mut self, _story_id: &str, _message: Self::Message) {}
+
+        fn view<'a>(&'a self, story_id: &str, _theme: &'a ()) -> Element<'a, Self::Message> {
+            text(story_id.to_string()).into()
         }
     }
+
+    fn storybook() -> Storybook<Stories> {
+        Storybook::with_fonts(
+            Settings::default(),
+            FontRegistry::new(&[]),
+            Startup::default(),
+        )
+        .0
+    }
+
+    fn press(app: &mut Storybook<Stories>, button: GamepadButton) {
+        let _ = app.update(Message::GamepadInput(GamepadEvent::ButtonPressed(button)));
+    }
+
+    fn story(id: &str) -> SidebarFocus {
+        SidebarFocus::Story(id.to_string())
+    }
+
+    #[test]
+    fn menu_focuses_the_selected_story() {
+        let mut app = storybook();
+        assert_eq!(app.selected, "button");
+
+        press(&mut app, GamepadButton::Menu);
+        assert_eq!(app.sidebar_focus(), Some(&story("button")));
+    }
+
+    #[test]
+    fn dpad_walks_the_focus_order() {
+        let mut app = storybook();
+        press(&mut app, GamepadButton::Menu);
+
+        let order = [
+            SidebarFocus::Theme,
+            SidebarFocus::Compare,
+            SidebarFocus::Editor,
+            SidebarFocus::Search,
+            story("button"),
+            story("slider"),
+            story("card"),
+            story(FONTS_STORY_ID),
+        ];
+        assert_eq!(
+            focus_order(
+                &app.sidebar_config,
+                &app.search_query,
+                app.sidebar_controls()
+            ),
+            order
+        );
+
+        for _ in 0..order.len() {
+            press(&mut app, GamepadButton::DPad(Direction::Up));
+        }
+        assert_eq!(app.sidebar_focus(), Some(&SidebarFocus::Theme));
+
+        for expected in &order[1..] {
+            press(&mut app, GamepadButton::DPad(Direction::Down));
+            assert_eq!(app.sidebar_focus(), Some(expected));
+        }
+
+        // The focus stays on the last control
+        press(&mut app, GamepadButton::DPad(Direction::Down));
+        assert_eq!(app.sidebar_focus(), Some(&story(FONTS_STORY_ID)));
+    }
+
+    #[test]
+    fn a_opens_the_focused_story() {
+        let mut app = storybook();
+        press(&mut app, GamepadButton::Menu);
+        press(&mut app, GamepadButton::DPad(Direction::Down));
+        assert_eq!(app.sidebar_focus(), Some(&story("slider")));
+
+        press(&mut app, GamepadButton::A);
+        assert_eq!(app.selected, "slider");
+        assert_eq!(app.sidebar_focus(), None);
+    }
+
+    #[test]
+    fn a_on_the_theme_picker_picks_the_next_theme() {
+        let mut app = storybook();
+        press(&mut app, GamepadButton::Menu);
+        for _ in 0..4 {
+            press(&mut app, GamepadButton::DPad(Direction::Up));
+        }
+        assert_eq!(app.sidebar_focus(), Some(&SidebarFocus::Theme));
+        assert_eq!(app.preferences.theme_mode(), &ThemeMode::System);
+
+        let expected = [
+            ThemeMode::Named("light".to_string()),
+            ThemeMode::Named("dark".to_string()),
+            ThemeMode::System,
+        ];
+        for mode in expected {
+            press(&mut app, GamepadButton::A);
+            assert_eq!(app.preferences.theme_mode(), &mode);
+            assert_eq!(app.sidebar_focus(), Some(&SidebarFocus::Theme));
+        }
+    }
+
+    #[test]
+    fn a_on_compare_and_editor_toggles_them() {
+        let mut app = storybook();
+        press(&mut app, GamepadButton::Menu);
+        for _ in 0..3 {
+            press(&mut app, GamepadButton::DPad(Direction::Up));
+        }
+        assert_eq!(app.sidebar_focus(), Some(&SidebarFocus::Compare));
+        press(&mut app, GamepadButton::A);
+        assert!(app.compare);
+
+        press(&mut app, GamepadButton::DPad(Direction::Down));
+        assert_eq!(app.sidebar_focus(), Some(&SidebarFocus::Editor));
+        press(&mut app, GamepadButton::A);
+        assert!(app.editor.open);
+    }
+
+    #[test]
+    fn b_menu_and_right_leave_the_sidebar() {
+        let mut app = storybook();
+
+        for button in [
+            GamepadButton::B,
+            GamepadButton::Menu,
+            GamepadButton::DPad(Direction::Right),
+        ] {
+            press(&mut app, GamepadButton::Menu);
+            press(&mut app, GamepadButton::DPad(Direction::Down));
+            assert_eq!(app.sidebar_focus(), Some(&story("slider")));
+
+            press(&mut app, button);
+            assert_eq!(app.sidebar_focus(), None);
+            assert_eq!(app.selected, "button");
+        }
+    }
+
+    #[test]
+    fn sticks_reach_the_story_while_the_sidebar_is_focused() {
+        let mut app = storybook();
+        let mut events = app.gamepad_events.subscribe(8);
+        let stick = |x| GamepadEvent::StickMoved {
+            stick: Stick::Left,
+            position: iced::Vector::new(x, 0.0),
+        };
+
+        let _ = app.update(Message::GamepadInput(stick(1.0)));
+        press(&mut app, GamepadButton::Menu);
+        let _ = app.update(Message::GamepadInput(stick(0.0)));
+        press(&mut app, GamepadButton::B);
+        assert_eq!(app.sidebar_focus(), None);
+
+        assert_eq!(events.try_recv().ok(), Some(stick(1.0)));
+        assert_eq!(events.try_recv().ok(), Some(stick(0.0)));
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn other_input_reaches_the_story() {
+        let mut app = storybook();
+
+        for button in [
+            GamepadButton::A,
+            GamepadButton::B,
+            GamepadButton::DPad(Direction::Down),
+        ] {
+            press(&mut app, button);
+            assert_eq!(app.sidebar_focus(), None);
+        }
+    }
+
+    #[test]
+    fn menu_is_ignored_when_embedded() {
+        let settings = Settings {
+            embed: true,
+            ..Settings::default()
+        };
+        let mut app =
+            Storybook::<Stories>::with_fonts(settings, FontRegistry::new(&[]), Startup::default())
+                .0;
+
+        press(&mut app, GamepadButton::Menu);
+        assert_eq!(app.sidebar_focus(), None);
+    }
 }
@@ -0,0 +1,779 @@
+//! Story canvas: the area the selected story is rendered in
+//!
+//! By default the story fills the space next to the sidebar. Picking a viewport
+//! renders it in a fixed-size frame instead, to check components at the sizes of
+//! the devices they ship on. The story can be zoomed (command + scroll or the
+//! toolbar buttons) and panned by dragging, on a background picked in the toolbar
+//! (stories can set a default in `StoryMeta::background`). A layout overlay
+//! outlines every widget of the story, optionally with its padding and spacing.
+//! Rulers, a pixel grid and a measure tool (drag between two points) check
+//! alignment and sizes in the story's logical pixels.
+
+mod guides;
+mod stage;
+
+use iced::widget::{container, pick_list, text, text_input, Row};
+use iced::{Alignment, Color, Element, Length, Point, Size, Vector};
+
+pub(crate) use guides::GuideColors;
+pub(crate) use stage::Stage;
+
+use crate::theme::SidebarTheme;
+use crate::tokens::{color_hex, parse_hex_color};
+use crate::toolbar::toolbar_button;
+
+/// Largest custom viewport edge, in logical pixels
+const MAX_VIEWPORT_EDGE: f32 = 8192.0;
+
+/// Zoom limits (25% to 400%)
+pub(crate) const MIN_ZOOM: f32 = 0.25;
+pub(crate) const MAX_ZOOM: f32 = 4.0;
+
+/// Zoom levels of the zoom in and out buttons
+const ZOOM_STEPS: [f32; 15] = [
+    0.25, 0.33, 0.5, 0.67, 0.75, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0,
+];
+
+/// A device size stories can be rendered at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub name: &'static str,
+    /// Width in logical pixels (landscape)
+    pub width: f32,
+    /// Height in logical pixels (landscape)
+    pub height: f32,
+}
+
+impl Viewport {
+    pub const fn new(name: &'static str, width: f32, height: f32) -> Self {
+        Self {
+            name,
+            width,
+            height,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+/// Viewport presets offered by default
+pub const DEFAULT_VIEWPORTS: &[Viewport] = &[
+    Viewport::new("Handheld", 1280.0, 800.0),
+    Viewport::new("TV", 1920.0, 1080.0),
+    Viewport::new("Small panel", 800.0, 480.0),
+];
+
+/// An entry in the viewport picker
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ViewportChoice {
+    /// Fill the space left of the window (no frame)
+    #[default]
+    Fill,
+    Preset(Viewport),
+    /// Size entered in the toolbar
+    Custom,
+}
+
+impl std::fmt::Display for ViewportChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fill => f.write_str("Fill window"),
+            Self::Preset(viewport) => write!(
+                f,
+                "{} {}×{}",
+                viewport.name, viewport.width, viewport.height
+            ),
+            Self::Custom => f.write_str("Custom"),
+        }
+    }
+}
+
+/// What stories are drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CanvasBackground {
+    /// The theme's content background (`SidebarTheme::content_background`)
+    #[default]
+    Theme,
+    White,
+    Black,
+    /// Light checkerboard, showing where the story is transparent
+    Checkerboard,
+    Custom(Color),
+}
+
+impl std::fmt::Display for CanvasBackground {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Theme => "Theme background",
+            Self::White => "White",
+            Self::Black => "Black",
+            Self::Checkerboard => "Checkerboard",
+            Self::Custom(_) => "Custom",
+        })
+    }
+}
+
+/// Layout debug overlay drawn over the story
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LayoutOverlay {
+    #[default]
+    Off,
+    /// Outline the bounds of every widget
+    Outlines,
+    /// Outlines, plus the space around each widget's children (padding or
+    /// alignment) and between them (spacing)
+    Spacing,
+}
+
+/// Pixel grid drawn over the story
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelGrid {
+    #[default]
+    Off,
+    Four,
+    Eight,
+}
+
+impl PixelGrid {
+    pub const ALL: [PixelGrid; 3] = [PixelGrid::Off, PixelGrid::Four, PixelGrid::Eight];
+
+    /// Distance between grid lines, in story pixels
+    pub fn spacing(self) -> Option<f32> {
+        match self {
+            Self::Off => None,
+            Self::Four => Some(4.0),
+            Self::Eight => Some(8.0),
+        }
+    }
+}
+
+impl std::fmt::Display for PixelGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Off => "No grid",
+            Self::Four => "4px grid",
+            Self::Eight => "8px grid",
+        })
+    }
+}
+
+/// Messages from the canvas controls of the toolbar
+#[derive(Debug, Clone)]
+pub enum CanvasMessage {
+    SetViewport(ViewportChoice),
+    /// Text of the custom width field changed
+    CustomWidthChanged(String),
+    /// Text of the custom height field changed
+    CustomHeightChanged(String),
+    /// Swap the frame's width and height
+    Rotate,
+    ZoomIn,
+    ZoomOut,
+    /// Zoom to 100%
+    ResetZoom,
+    /// Zoom so the whole frame is visible
+    FitToWindow,
+    /// Zoom keeping the story point under `anchor` in place (command + scroll);
+    /// `anchor` is relative to the stage and `pan` is the pan shown
+    ZoomAround {
+        zoom: f32,
+        anchor: Point,
+        pan: Vector,
+    },
+    /// Pan the story (drag or scroll)
+    Pan(Vector),
+    /// The space available to the story changed
+    StageResized(Size),
+    SetBackground(CanvasBackground),
+    /// Text of the custom background color field changed
+    CustomBackgroundChanged(String),
+    /// Show or hide widget outlines (`L`)
+    ToggleOutlines,
+    /// Show or hide padding and spacing, with outlines (`Shift+L`)
+    ToggleSpacing,
+    /// Show or hide rulers (`R`)
+    ToggleRulers,
+    SetGrid(PixelGrid),
+    /// Turn the measure tool on or off (`M`)
+    ToggleMeasure,
+}
+
+/// State of the story canvas
+pub struct StoryCanvas {
+    viewport: ViewportChoice,
+    /// Frame is in portrait orientation (width and height swapped)
+    rotated: bool,
+    /// Last valid custom size
+    custom: Size,
+    /// Text of the custom width and height fields (may not parse yet)
+    custom_drafts: [String; 2],
+    zoom: f32,
+    /// Offset of the story from the stage's top left, in screen pixels
+    pan: Vector,
+    /// Space available to the story, as last reported by the stage
+    available: Size,
+    /// Background picked in the toolbar, overriding the story's default
+    background: Option<CanvasBackground>,
+    /// Default background of the selected story
+    story_background: Option<CanvasBackground>,
+    /// Text of the custom background color field (may not parse yet)
+    custom_background_draft: String,
+    layout_overlay: LayoutOverlay,
+    rulers: bool,
+    grid: PixelGrid,
+    /// Dragging over the story measures instead of interacting with it
+    measure: bool,
+}
+
+impl Default for StoryCanvas {
+    fn default() -> Self {
+        let custom = DEFAULT_VIEWPORTS[0].size();
+
+        Self {
+            viewport: ViewportChoice::Fill,
+            rotated: false,
+            custom,
+            custom_drafts: [custom.width.to_string(), custom.height.to_string()],
+            zoom: 1.0,
+            pan: Vector::ZERO,
+            available: Size::ZERO,
+            background: None,
+            story_background: None,
+            custom_background_draft: color_hex(Color::WHITE),
+            layout_overlay: LayoutOverlay::Off,
+            rulers: false,
+            grid: PixelGrid::Off,
+            measure: false,
+        }
+    }
+}
+
+impl StoryCanvas {
+    pub fn update(&mut self, message: CanvasMessage) {
+        match message {
+            CanvasMessage::SetViewport(viewport) => {
+                self.viewport = viewport;
+                self.pan = Vector::ZERO;
+            }
+            CanvasMessage::CustomWidthChanged(value) => {
+                if let Some(width) = parse_edge(&value) {
+                    self.custom.width = width;
+                }
+                self.custom_drafts[0] = value;
+            }
+            CanvasMessage::CustomHeightChanged(value) => {
+                if let Some(height) = parse_edge(&value) {
+                    self.custom.height = height;
+                }
+                self.custom_drafts[1] = value;
+            }
+            CanvasMessage::Rotate => {
+                self.rotated = !self.rotated;
+                self.pan = Vector::ZERO;
+            }
+            CanvasMessage::ZoomIn => {
+                let zoom = ZOOM_STEPS
+                    .into_iter()
+                    .find(|step| *step > self.zoom + 0.001);
+                self.zoom_around_center(zoom.unwrap_or(MAX_ZOOM));
+            }
+            CanvasMessage::ZoomOut => {
+                let zoom = ZOOM_STEPS
+                    .into_iter()
+                    .rfind(|step| *step < self.zoom - 0.001);
+                self.zoom_around_center(zoom.unwrap_or(MIN_ZOOM));
+            }
+            CanvasMessage::ResetZoom => {
+                self.zoom = 1.0;
+                self.pan = Vector::ZERO;
+            }
+            CanvasMessage::FitToWindow => {
+                // Unframed stories already fit the window width
+                self.zoom = match self.frame_size() {
+                    Some(frame) => (self.available.width / (frame.width + 2.0))
+                        .min(self.available.height / (frame.height + 2.0))
+                        .clamp(MIN_ZOOM, MAX_ZOOM),
+                    None => 1.0,
+                };
+                self.pan = Vector::ZERO;
+            }
+            CanvasMessage::ZoomAround { zoom, anchor, pan } => {
+                self.pan = pan;
+                self.zoom_around(zoom, anchor);
+            }
+            CanvasMessage::Pan(pan) => self.pan = pan,
+            CanvasMessage::StageResized(available) => self.available = available,
+            CanvasMessage::SetBackground(background) => self.background = Some(background),
+            CanvasMessage::CustomBackgroundChanged(value) => {
+                if let Some(color) = parse_hex_color(&value) {
+                    self.background = Some(CanvasBackground::Custom(color));
+                }
+                self.custom_background_draft = value;
+            }
+            CanvasMessage::ToggleOutlines => {
+                self.layout_overlay = match self.layout_overlay {
+                    LayoutOverlay::Off => LayoutOverlay::Outlines,
+                    LayoutOverlay::Outlines | LayoutOverlay::Spacing => LayoutOverlay::Off,
+                };
+            }
+            CanvasMessage::ToggleSpacing => {
+                self.layout_overlay = match self.layout_overlay {
+                    LayoutOverlay::Spacing => LayoutOverlay::Outlines,
+                    LayoutOverlay::Off | LayoutOverlay::Outlines => LayoutOverlay::Spacing,
+                };
+            }
+            CanvasMessage::ToggleRulers => self.rulers = !self.rulers,
+            CanvasMessage::SetGrid(grid) => self.grid = grid,
+            CanvasMessage::ToggleMeasure => self.measure = !self.measure,
+        }
+    }
+
+    pub fn layout_overlay(&self) -> LayoutOverlay {
+        self.layout_overlay
+    }
+
+    pub fn rulers(&self) -> bool {
+        self.rulers
+    }
+
+    pub fn grid(&self) -> PixelGrid {
+        self.grid
+    }
+
+    pub fn measure(&self) -> bool {
+        self.measure
+    }
+
+    /// Back to the default viewport, zoom, background and guides (the story's
+    /// default background is kept)
+    pub fn reset(&mut self) {
+        let story_background = self.story_background;
+        *self = Self::default();
+        self.set_story_background(story_background);
+    }
+
+    /// Switch to a story's default background
+    ///
+    /// The background picked in the toolbar is kept, unless the story has a
+    /// background of its own.
+    pub fn set_story_background(&mut self, background: Option<CanvasBackground>) {
+        self.story_background = background;
+        match background {
+            Some(CanvasBackground::Custom(color)) => {
+                self.background = None;
+                self.custom_background_draft = color_hex(color);
+            }
+            Some(_) => self.background = None,
+            None => {}
+        }
+    }
+
+    /// Background shown: picked in the toolbar, else the story's default
+    pub fn background(&self) -> CanvasBackground {
+        self.background
+            .or(self.story_background)
+            .unwrap_or_default()
+    }
+
+    /// Color of the custom background option
+    fn custom_background(&self) -> Color {
+        match self.background() {
+            CanvasBackground::Custom(color) => color,
+            _ => parse_hex_color(&self.custom_background_draft).unwrap_or(Color::WHITE),
+        }
+    }
+
+    /// Show the top left of the story (e.g. after switching stories)
+    pub fn reset_pan(&mut self) {
+        self.pan = Vector::ZERO;
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn pan(&self) -> Vector {
+        self.pan
+    }
+
+    /// Zoom keeping the story point under `anchor` (relative to the stage) in place
+    fn zoom_around(&mut self, zoom: f32, anchor: Point) {
+        let anchor = Vector::new(anchor.x, anchor.y);
+        let point = (anchor - self.pan) * (1.0 / self.zoom);
+
+        self.zoom = zoom;
+        self.pan = anchor - point * zoom;
+    }
+
+    fn zoom_around_center(&mut self, zoom: f32) {
+        let center = Point::new(self.available.width / 2.0, self.available.height / 2.0);
+        self.zoom_around(zoom, center);
+    }
+
+    /// Size of the story frame, or `None` when the story fills the canvas
+    pub fn frame_size(&self) -> Option<Size> {
+        let size = match self.viewport {
+            ViewportChoice::Fill => return None,
+            ViewportChoice::Preset(viewport) => viewport.size(),
+            ViewportChoice::Custom => self.custom,
+        };
+
+        Some(if self.rotated {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        })
+    }
+}
+
+/// Parse a custom viewport edge (whole logical pixels)
+fn parse_edge(value: &str) -> Option<f32> {
+    let pixels = value.trim().parse::<u32>().ok()? as f32;
+    (1.0..=MAX_VIEWPORT_EDGE)
+        .contains(&pixels)
+        .then_some(pixels)
+}
+
+/// Canvas controls of the toolbar, in groups kept on one line when it wraps:
+/// viewport, background, layout overlay and guides, zoom
+pub(crate) fn canvas_controls<'a>(
+    canvas: &'a StoryCanvas,
+    viewports: &'static [Viewport],
+    theme: &'a dyn SidebarTheme,
+) -> Vec<Element<'a, CanvasMessage>> {
+    let button_font = theme.button_font();
+    let group = || Row::new().spacing(8).align_y(Alignment::Center);
+
+    // Viewport, with size fields for a custom one
+    let choices: Vec<ViewportChoice> = std::iter::once(ViewportChoice::Fill)
+        .chain(viewports.iter().copied().map(ViewportChoice::Preset))
+        .chain(std::iter::once(ViewportChoice::Custom))
+        .collect();
+    let picker = pick_list(choices, Some(canvas.viewport), CanvasMessage::SetViewport)
+        .text_size(theme.button_size())
+        .font(button_font.font)
+        .text_shaping(button_font.shaping)
+        .padding([6, 8])
+        .width(Length::Fixed(220.0))
+        .style(move |_, status| theme.pick_list_style(status))
+        .menu_style(move |_| theme.menu_style());
+
+    let mut viewport = group().push(picker);
+
+    if canvas.viewport == ViewportChoice::Custom {
+        let edge_input = |value: &'a String, on_input: fn(String) -> CanvasMessage| {
+            text_input("px", value)
+                .on_input(on_input)
+                .size(theme.button_size())
+                .padding([6, 8])
+                .width(Length::Fixed(72.0))
+                .style(move |_, status| theme.input_style(status))
+        };
+
+        viewport = viewport
+            .push(edge_input(
+                &canvas.custom_drafts[0],
+                CanvasMessage::CustomWidthChanged,
+            ))
+            .push(text("×").color(theme.text_secondary()))
+            .push(edge_input(
+                &canvas.custom_drafts[1],
+                CanvasMessage::CustomHeightChanged,
+            ));
+    }
+
+    let frame_size = canvas.frame_size();
+    viewport = viewport.push(toolbar_button(
+        "Rotate",
+        canvas.rotated,
+        frame_size.map(|_| CanvasMessage::Rotate),
+        theme,
+    ));
+
+    if let Some(size) = frame_size {
+        viewport = viewport.push(
+            text(format!("{} × {}", size.width, size.height))
+                .size(theme.button_size())
+                .color(theme.text_secondary()),
+        );
+    }
+
+    // Background, with a hex field for a custom color
+    let background = canvas.background();
+    let backgrounds = vec![
+        CanvasBackground::Theme,
+        CanvasBackground::White,
+        CanvasBackground::Black,
+        CanvasBackground::Checkerboard,
+        CanvasBackground::Custom(canvas.custom_background()),
+    ];
+    let mut backdrop = group().push(
+        pick_list(backgrounds, Some(background), CanvasMessage::SetBackground)
+            .text_size(theme.button_size())
+            .font(button_font.font)
+            .text_shaping(button_font.shaping)
+            .padding([6, 8])
+            .width(Length::Fixed(170.0))
+            .style(move |_, status| theme.pick_list_style(status))
+            .menu_style(move |_| theme.menu_style()),
+    );
+    if let CanvasBackground::Custom(_) = background {
+        backdrop = backdrop.push(
+            text_input("#RRGGBB", &canvas.custom_background_draft)
+                .on_input(CanvasMessage::CustomBackgroundChanged)
+                .size(theme.button_size())
+                .padding([6, 8])
+                .width(Length::Fixed(96.0))
+                .style(move |_, status| theme.input_style(status)),
+        );
+    }
+
+    // Layout overlay and guides
+    let overlay = canvas.layout_overlay;
+    let guides = group()
+        .push(toolbar_button(
+            "Outlines",
+            overlay != LayoutOverlay::Off,
+            Some(CanvasMessage::ToggleOutlines),
+            theme,
+        ))
+        .push(toolbar_button(
+            "Spacing",
+            overlay == LayoutOverlay::Spacing,
+            Some(CanvasMessage::ToggleSpacing),
+            theme,
+        ))
+        .push(toolbar_button(
+            "Rulers",
+            canvas.rulers,
+            Some(CanvasMessage::ToggleRulers),
+            theme,
+        ))
+        .push(
+            pick_list(PixelGrid::ALL, Some(canvas.grid), CanvasMessage::SetGrid)
+                .text_size(theme.button_size())
+                .font(button_font.font)
+                .text_shaping(button_font.shaping)
+                .padding([6, 8])
+                .width(Length::Fixed(110.0))
+                .style(move |_, status| theme.pick_list_style(status))
+                .menu_style(move |_| theme.menu_style()),
+        )
+        .push(toolbar_button(
+            "Measure",
+            canvas.measure,
+            Some(CanvasMessage::ToggleMeasure),
+            theme,
+        ));
+
+    // Zoom: out, level (resets to 100%), in, fit
+    let zoom_level = canvas.zoom;
+    let zoom = group()
+        .push(toolbar_button(
+            "−",
+            false,
+            (zoom_level > MIN_ZOOM).then_some(CanvasMessage::ZoomOut),
+            theme,
+        ))
+        .push(toolbar_button(
+            format!("{:.0}%", zoom_level * 100.0),
+            false,
+            Some(CanvasMessage::ResetZoom),
+            theme,
+        ))
+        .push(toolbar_button(
+            "+",
+            false,
+            (zoom_level < MAX_ZOOM).then_some(CanvasMessage::ZoomIn),
+            theme,
+        ))
+        .push(toolbar_button(
+            "Fit",
+            false,
+            Some(CanvasMessage::FitToWindow),
+            theme,
+        ));
+
+    vec![viewport.into(), backdrop.into(), guides.into(), zoom.into()]
+}
+
+/// Render content in a frame of a fixed size, clipping what overflows (the stage
+/// outlines the frame)
+pub fn framed<'a, Message: 'a>(content: Element<'a, Message>, size: Size) -> Element<'a, Message> {
+    container(content)
+        .width(Length::Fixed(size.width))
+        .height(Length::Fixed(size.height))
+        .clip(true)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_picked_background_for_stories_without_one() {
+        let mut canvas = StoryCanvas::default();
+        canvas.update(CanvasMessage::SetBackground(CanvasBackground::Black));
+
+        canvas.set_story_background(None);
+        assert_eq!(canvas.background(), CanvasBackground::Black);
+    }
+
+    #[test]
+    fn story_backgrounds_replace_the_picked_one() {
+        let mut canvas = StoryCanvas::default();
+        canvas.update(CanvasMessage::SetBackground(CanvasBackground::Black));
+
+        canvas.set_story_background(Some(CanvasBackground::Checkerboard));
+        assert_eq!(canvas.background(), CanvasBackground::Checkerboard);
+
+        // Picking again overrides the story's background until the next switch
+        canvas.update(CanvasMessage::SetBackground(CanvasBackground::White));
+        assert_eq!(canvas.background(), CanvasBackground::White);
+        canvas.set_story_background(None);
+        assert_eq!(canvas.background(), CanvasBackground::White);
+    }
+
+    #[test]
+    fn story_custom_backgrounds_fill_the_color_field() {
+        let mut canvas = StoryCanvas::default();
+        let color = Color::from_rgb8(0x12, 0x34, 0x56);
+
+        canvas.set_story_background(Some(CanvasBackground::Custom(color)));
+        assert_eq!(canvas.background(), CanvasBackground::Custom(color));
+        assert_eq!(canvas.custom_background_draft, "#123456");
+    }
+
+    #[test]
+    fn reset_keeps_the_story_background() {
+        let mut canvas = StoryCanvas::default();
+        canvas.set_story_background(Some(CanvasBackground::Checkerboard));
+        canvas.update(CanvasMessage::SetBackground(CanvasBackground::Black));
+
+        canvas.reset();
+        assert_eq!(canvas.background(), CanvasBackground::Checkerboard);
+    }
+
+    fn zoomed(zoom: f32) -> StoryCanvas {
+        StoryCanvas {
+            zoom,
+            ..StoryCanvas::default()
+        }
+    }
+
+    fn step(zoom: f32, message: CanvasMessage) -> f32 {
+        let mut canvas = zoomed(zoom);
+        canvas.update(message);
+        canvas.zoom()
+    }
+
+    #[test]
+    fn zoom_steps_from_presets() {
+        assert_eq!(step(1.0, CanvasMessage::ZoomIn), 1.1);
+        assert_eq!(step(1.0, CanvasMessage::ZoomOut), 0.9);
+        // Within the epsilon of a preset counts as the preset
+        assert_eq!(step(1.0005, CanvasMessage::ZoomIn), 1.1);
+        assert_eq!(step(0.9995, CanvasMessage::ZoomOut), 0.9);
+    }
+
+    #[test]
+    fn zoom_steps_between_presets() {
+        assert_eq!(step(0.3, CanvasMessage::ZoomIn), 0.33);
+        assert_eq!(step(0.3, CanvasMessage::ZoomOut), 0.25);
+        assert_eq!(step(1.2, CanvasMessage::ZoomIn), 1.25);
+        assert_eq!(step(1.2, CanvasMessage::ZoomOut), 1.1);
+    }
+
+    #[test]
+    fn zoom_steps_stop_at_the_limits() {
+        assert_eq!(step(MAX_ZOOM, CanvasMessage::ZoomIn), MAX_ZOOM);
+        assert_eq!(step(MIN_ZOOM, CanvasMessage::ZoomOut), MIN_ZOOM);
+        assert_eq!(step(3.5, CanvasMessage::ZoomIn), MAX_ZOOM);
+        assert_eq!(step(0.3, CanvasMessage::ZoomOut), MIN_ZOOM);
+    }
+
+    #[test]
+    fn zoom_around_keeps_the_anchor_in_place() {
+        let mut canvas = zoomed(1.5);
+        let anchor = Point::new(300.0, 200.0);
+        let pan = Vector::new(-40.0, 25.0);
+        let story_point = |canvas: &StoryCanvas| {
+            (Vector::new(anchor.x, anchor.y) - canvas.pan()) * (1.0 / canvas.zoom())
+        };
+
+        canvas.update(CanvasMessage::Pan(pan));
+        let before = story_point(&canvas);
+        canvas.update(CanvasMessage::ZoomAround {
+            zoom: 2.5,
+            anchor,
+            pan,
+        });
+
+        assert_eq!(canvas.zoom(), 2.5);
+        let after = story_point(&canvas);
+        assert!((after.x - before.x).abs() < 1e-4, "{after:?} != {before:?}");
+        assert!((after.y - before.y).abs() < 1e-4, "{after:?} != {before:?}");
+    }
+
+    #[test]
+    fn zoom_buttons_keep_the_stage_center_in_place() {
+        let mut canvas = StoryCanvas::default();
+        canvas.update(CanvasMessage::StageResized(Size::new(800.0, 600.0)));
+
+        canvas.update(CanvasMessage::ZoomIn);
+        assert_eq!(canvas.zoom(), 1.1);
+        // The story point at the center (400, 300) stays there
+        assert!((canvas.pan().x - (400.0 - 400.0 * 1.1)).abs() < 1e-3);
+        assert!((canvas.pan().y - (300.0 - 300.0 * 1.1)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fit_scales_frames_to_the_stage() {
+        let mut canvas = StoryCanvas::default();
+        canvas.update(CanvasMessage::SetViewport(ViewportChoice::Preset(
+            DEFAULT_VIEWPORTS[0],
+        )));
+        canvas.update(CanvasMessage::StageResized(Size::new(642.0, 1000.0)));
+        canvas.update(CanvasMessage::Pan(Vector::new(10.0, 10.0)));
+
+        canvas.update(CanvasMessage::FitToWindow);
+        // 1280 wide plus the 1px border on each side
+        assert_eq!(canvas.zoom(), 642.0 / 1282.0);
+        assert_eq!(canvas.pan(), Vector::ZERO);
+    }
+
+    #[test]
+    fn fit_clamps_to_the_zoom_limits() {
+        let mut canvas = StoryCanvas::default();
+        canvas.update(CanvasMessage::SetViewport(ViewportChoice::Preset(
+            DEFAULT_VIEWPORTS[0],
+        )));
+
+        // Before the stage reported its size
+        canvas.update(CanvasMessage::FitToWindow);
+        assert_eq!(canvas.zoom(), MIN_ZOOM);
+
+        canvas.update(CanvasMessage::StageResized(Size::new(100.0, 100.0)));
+        canvas.update(CanvasMessage::FitToWindow);
+        assert_eq!(canvas.zoom(), MIN_ZOOM);
+
+        canvas.update(CanvasMessage::CustomWidthChanged("10".to_string()));
+        canvas.update(CanvasMessage::CustomHeightChanged("10".to_string()));
+        canvas.update(CanvasMessage::SetViewport(ViewportChoice::Custom));
+        canvas.update(CanvasMessage::StageResized(Size::new(1000.0, 1000.0)));
+        canvas.update(CanvasMessage::FitToWindow);
+        assert_eq!(canvas.zoom(), MAX_ZOOM);
+    }
+
+    #[test]
+    fn fit_resets_unframed_stories() {
+        let mut canvas = zoomed(2.0);
+        canvas.update(CanvasMessage::StageResized(Size::ZERO));
+
+        canvas.update(CanvasMessage::FitToWindow);
+        assert_eq!(canvas.zoom(), 1.0);
+    }
+}
@@ -0,0 +1,357 @@
+//! Guides drawn by the stage: frame outline, rulers, pixel grid and measurements
+//!
+//! Rulers and measurements are labeled in the story's logical pixels, whatever
+//! the zoom level.
+
+use std::ops::RangeInclusive;
+
+use iced::advanced::renderer;
+use iced::advanced::text::{self, Text};
+use iced::alignment;
+use iced::{Border, Color, Font, Pixels, Point, Rectangle, Size, Vector};
+
+use crate::theme::SidebarTheme;
+
+/// Thickness of the rulers
+pub(crate) const RULER_SIZE: f32 = 20.0;
+
+/// Smallest distance between labeled ruler ticks, in screen pixels
+const MIN_LABEL_SPACING: f32 = 48.0;
+/// Smallest distance between grid lines or unlabeled ticks, in screen pixels
+const MIN_LINE_SPACING: f32 = 4.0;
+
+const GRID_COLOR: Color = Color::from_rgba(0.20, 0.55, 1.0, 0.25);
+const MEASURE_COLOR: Color = Color::from_rgb(0.95, 0.28, 0.13);
+
+/// Colors of the stage chrome, from the sidebar theme
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct GuideColors {
+    pub(crate) background: Color,
+    pub(crate) text: Color,
+    pub(crate) divider: Color,
+}
+
+impl GuideColors {
+    pub(crate) fn from_theme(theme: &dyn SidebarTheme) -> Self {
+        Self {
+            background: theme.sidebar_background(),
+            text: theme.text_secondary(),
+            divider: theme.divider_color(),
+        }
+    }
+}
+
+fn fill<R: renderer::Renderer>(renderer: &mut R, bounds: Rectangle, color: Color) {
+    if bounds.width > 0.0 && bounds.height > 0.0 {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                ..renderer::Quad::default()
+            },
+            color,
+        );
+    }
+}
+
+fn outline<R: renderer::Renderer>(renderer: &mut R, bounds: Rectangle, width: f32, color: Color) {
+    renderer.fill_quad(
+        renderer::Quad {
+            bounds,
+            border: Border {
+                color,
+                width,
+                radius: 0.0.into(),
+            },
+            ..renderer::Quad::default()
+        },
+        Color::TRANSPARENT,
+    );
+}
+
+fn label<R: text::Renderer<Font = Font>>(
+    renderer: &mut R,
+    content: String,
+    position: Point,
+    size: f32,
+    color: Color,
+    clip: Rectangle,
+) {
+    renderer.fill_text(
+        Text {
+            content,
+            bounds: Size::INFINITE,
+            size: Pixels(size),
+            line_height: text::LineHeight::default(),
+            font: Font::DEFAULT,
+            align_x: text::Alignment::Left,
+            align_y: alignment::Vertical::Top,
+            shaping: text::Shaping::Basic,
+            wrapping: text::Wrapping::None,
+        },
+        position,
+        color,
+        clip,
+    );
+}
+
+/// Outline of a viewport frame, just outside its (screen) bounds
+pub(crate) fn draw_frame<R: renderer::Renderer>(renderer: &mut R, frame: Rectangle, color: Color) {
+    outline(renderer, frame.expand(1.0), 1.0, color);
+}
+
+/// Grid lines every `spacing` story pixels over `area`, in story layout coordinates
+/// (drawn with the stage's transformation)
+pub(crate) fn draw_grid<R: renderer::Renderer>(
+    renderer: &mut R,
+    area: Rectangle,
+    origin: Point,
+    spacing: f32,
+    zoom: f32,
+) {
+    if spacing * zoom < MIN_LINE_SPACING {
+        return;
+    }
+
+    let width = 1.0 / zoom;
+    for column in line_indices(area.x, area.x + area.width, origin.x, spacing) {
+        let x = origin.x + column as f32 * spacing;
+        fill(
+            renderer,
+            Rectangle::new(Point::new(x, area.y), Size::new(width, area.height)),
+            GRID_COLOR,
+        );
+    }
+
+    for row in line_indices(area.y, area.y + area.height, origin.y, spacing) {
+        let y = origin.y + row as f32 * spacing;
+        fill(
+            renderer,
+            Rectangle::new(Point::new(area.x, y), Size::new(area.width, width)),
+            GRID_COLOR,
+        );
+    }
+}
+
+/// Indices `i` of the lines at `origin + i * spacing` between `start` and `end`
+/// (inclusive)
+fn line_indices(start: f32, end: f32, origin: f32, spacing: f32) -> RangeInclusive<i32> {
+    let first = ((start - origin) / spacing).ceil() as i32;
+    let last = ((end - origin) / spacing).floor() as i32;
+    first..=last
+}
+
+/// Story pixels between labeled ticks and between all ticks at a zoom level
+fn ruler_steps(zoom: f32) -> (f32, f32) {
+    let mut step = 2.0;
+    while step * zoom < MIN_LABEL_SPACING {
+        step *= 2.0;
+    }
+
+    let minor = if step / 4.0 * zoom >= MIN_LINE_SPACING {
+        step / 4.0
+    } else {
+        step / 2.0
+    };
+
+    (step, minor)
+}
+
+/// Rulers along the top and left of the stage; `origin` is the story's top left on screen
+pub(crate) fn draw_rulers<R: text::Renderer<Font = Font>>(
+    renderer: &mut R,
+    bounds: Rectangle,
+    origin: Point,
+    zoom: f32,
+    colors: GuideColors,
+) {
+    let top = Rectangle::new(bounds.position(), Size::new(bounds.width, RULER_SIZE));
+    let left = Rectangle::new(bounds.position(), Size::new(RULER_SIZE, bounds.height));
+    fill(renderer, top, colors.background);
+    fill(renderer, left, colors.background);
+    fill(
+        renderer,
+        Rectangle::new(
+            Point::new(bounds.x, bounds.y + RULER_SIZE - 1.0),
+            Size::new(bounds.width, 1.0),
+        ),
+        colors.divider,
+    );
+    fill(
+        renderer,
+        Rectangle::new(
+            Point::new(bounds.x + RULER_SIZE - 1.0, bounds.y),
+            Size::new(1.0, bounds.height),
+        ),
+        colors.divider,
+    );
+
+    let (step, minor) = ruler_steps(zoom);
+    let tick_length = |value: f32| {
+        if value % step == 0.0 {
+            RULER_SIZE / 2.0
+        } else {
+            RULER_SIZE / 5.0
+        }
+    };
+
+    // Ticks along the top
+    let ticks = line_indices(
+        bounds.x + RULER_SIZE,
+        bounds.x + bounds.width,
+        origin.x,
+        minor * zoom,
+    );
+    for index in ticks {
+        let value = index as f32 * minor;
+        let x = (origin.x + value * zoom).round();
+        let length = tick_length(value);
+        fill(
+            renderer,
+            Rectangle::new(
+                Point::new(x, top.y + RULER_SIZE - length),
+                Size::new(1.0, length),
+            ),
+            colors.text,
+        );
+        if value % step == 0.0 {
+            label(
+                renderer,
+                format!("{value}"),
+                Point::new(x + 3.0, top.y + 2.0),
+                9.0,
+                colors.text,
+                top,
+            );
+        }
+    }
+
+    // Ticks along the left
+    let ticks = line_indices(
+        bounds.y + RULER_SIZE,
+        bounds.y + bounds.height,
+        origin.y,
+        minor * zoom,
+    );
+    for index in ticks {
+        let value = index as f32 * minor;
+        let y = (origin.y + value * zoom).round();
+        let length = tick_length(value);
+        fill(
+            renderer,
+            Rectangle::new(
+                Point::new(left.x + RULER_SIZE - length, y),
+                Size::new(length, 1.0),
+            ),
+            colors.text,
+        );
+        if value % step == 0.0 {
+            label(
+                renderer,
+                format!("{value}"),
+                Point::new(left.x + 2.0, y + 2.0),
+                9.0,
+                colors.text,
+                left,
+            );
+        }
+    }
+
+    // Corner
+    fill(
+        renderer,
+        Rectangle::new(bounds.position(), Size::new(RULER_SIZE, RULER_SIZE)),
+        colors.background,
+    );
+}
+
+/// A measurement between two story points (`start` and `end`, in story pixels),
+/// with `origin` and `zoom` mapping story pixels to the screen
+pub(crate) fn draw_measurement<R: text::Renderer<Font = Font>>(
+    renderer: &mut R,
+    start: Point,
+    end: Point,
+    origin: Point,
+    zoom: f32,
+    clip: Rectangle,
+) {
+    let to_screen = |point: Point| origin + Vector::new(point.x * zoom, point.y * zoom);
+    let (a, b) = (to_screen(start), to_screen(end));
+    let area = Rectangle::new(
+        Point::new(a.x.min(b.x), a.y.min(b.y)),
+        Size::new((a.x - b.x).abs(), (a.y - b.y).abs()),
+    );
+
+    // Bounding box of the two points, with the horizontal and vertical distance
+    fill(
+        renderer,
+        area,
+        Color {
+            a: 0.1,
+            ..MEASURE_COLOR
+        },
+    );
+    outline(renderer, area, 1.0, MEASURE_COLOR);
+    for point in [a, b] {
+        fill(
+            renderer,
+            Rectangle::new(point - Vector::new(3.0, 3.0), Size::new(6.0, 6.0)),
+            MEASURE_COLOR,
+        );
+    }
+
+    let (dx, dy) = ((end.x - start.x).abs(), (end.y - start.y).abs());
+    let distance = (dx * dx + dy * dy).sqrt();
+    let content = format!("{dx} × {dy}  ·  {distance:.1} px");
+
+    // Label next to the end point, on a solid background
+    let size = Size::new(content.chars().count() as f32 * 6.5 + 12.0, 20.0);
+    let position = b + Vector::new(10.0, 10.0);
+    fill(renderer, Rectangle::new(position, size), MEASURE_COLOR);
+    label(
+        renderer,
+        content,
+        position + Vector::new(6.0, 3.0),
+        11.0,
+        Color::WHITE,
+        clip,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruler_steps_by_zoom() {
+        assert_eq!(ruler_steps(0.25), (256.0, 64.0));
+        assert_eq!(ruler_steps(1.0), (64.0, 16.0));
+        assert_eq!(ruler_steps(4.0), (16.0, 4.0));
+    }
+
+    #[test]
+    fn ruler_ticks_keep_their_distance() {
+        for zoom in [0.25, 0.33, 0.5, 1.0, 1.1, 2.0, 3.0, 4.0] {
+            let (step, minor) = ruler_steps(zoom);
+
+            assert!(step * zoom >= MIN_LABEL_SPACING, "labels at {zoom}");
+            assert!(minor * zoom >= MIN_LINE_SPACING, "ticks at {zoom}");
+            // Labeled ticks fall on minor ticks, and labels are not needlessly sparse
+            assert_eq!(step % minor, 0.0, "{zoom}");
+            assert!(
+                step / 2.0 * zoom < MIN_LABEL_SPACING || step == 2.0,
+                "{zoom}"
+            );
+        }
+    }
+
+    #[test]
+    fn line_indices_cover_the_span() {
+        // Lines at 0, 8, 16 and 24 from an origin at 0
+        assert_eq!(line_indices(0.0, 24.0, 0.0, 8.0), 0..=3);
+        assert_eq!(line_indices(1.0, 23.0, 0.0, 8.0), 1..=2);
+        // Lines left of the origin have negative indices
+        assert_eq!(line_indices(-20.0, 10.0, 4.0, 8.0), -3..=0);
+        // A span between two lines holds none
+        assert!(line_indices(9.0, 15.0, 0.0, 8.0).is_empty());
+    }
+}
@@ -0,0 +1,787 @@
+//! The stage widget: renders the story zoomed and panned
+//!
+//! The story is laid out at its logical size and drawn through a scale and
+//! translation, so borders and icons can be inspected up close. Cursor
+//! positions are mapped back, which keeps the story interactive at any zoom.
+//! Guides (rulers, grid, measurements) are drawn over the story.
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::{tree, Operation, Tree, Widget};
+use iced::advanced::{mouse, overlay, renderer, text, Clipboard, Shell};
+use iced::keyboard;
+use iced::{Color, Element, Event, Font, Length, Point, Rectangle, Size, Transformation, Vector};
+
+use super::guides::{self, GuideColors};
+use super::{CanvasBackground, CanvasMessage, LayoutOverlay, MAX_ZOOM, MIN_ZOOM};
+
+/// Zoom factor per scrolled line with the command key held
+const ZOOM_PER_LINE: f32 = 1.1;
+/// Pixels panned per scrolled line
+const PAN_PER_LINE: f32 = 40.0;
+/// Pixels of a pixel-based scroll that count as one line when zooming
+const PIXELS_PER_LINE: f32 = 50.0;
+
+/// Checkerboard squares (screen pixels) and colors
+const CHECKER_SIZE: f32 = 8.0;
+const CHECKER_LIGHT: Color = Color::WHITE;
+const CHECKER_DARK: Color = Color::from_rgb(0.85, 0.85, 0.85);
+
+/// Outline colors of the layout overlay, by nesting depth
+const OUTLINE_COLORS: [Color; 4] = [
+    Color::from_rgba(0.90, 0.20, 0.20, 0.9),
+    Color::from_rgba(0.15, 0.45, 0.95, 0.9),
+    Color::from_rgba(0.95, 0.55, 0.05, 0.9),
+    Color::from_rgba(0.55, 0.25, 0.85, 0.9),
+];
+/// Fill of the space between a widget and its children (padding or alignment)
+const PADDING_COLOR: Color = Color::from_rgba(0.30, 0.75, 0.35, 0.3);
+/// Fill of the space between children (spacing)
+const SPACING_COLOR: Color = Color::from_rgba(0.85, 0.30, 0.80, 0.3);
+
+/// Story canvas that zooms and pans its content
+pub(crate) struct Stage<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+    zoom: f32,
+    pan: Vector,
+    padding: f32,
+    /// Lay the content out at the stage width (unframed stories)
+    fill_width: bool,
+    background: CanvasBackground,
+    /// Color of `CanvasBackground::Theme`
+    theme_background: Color,
+    layout_overlay: LayoutOverlay,
+    rulers: bool,
+    /// Grid spacing in story pixels
+    grid: Option<f32>,
+    /// Dragging measures instead of interacting with the story
+    measure: bool,
+    guide_colors: GuideColors,
+    on_change: Box<dyn Fn(CanvasMessage) -> Message + 'a>,
+}
+
+/// Interaction state of a stage
+#[derive(Default)]
+struct State {
+    modifiers: keyboard::Modifiers,
+    /// Cursor position and pan where a drag started
+    drag: Option<(Point, Vector)>,
+    /// Available size last reported with `CanvasMessage::StageResized`
+    reported: Option<Size>,
+    /// Last measurement (start and end, in story pixels)
+    measurement: Option<(Point, Point)>,
+    /// A measurement is being dragged
+    measuring: bool,
+}
+
+impl<'a, Message, Theme, Renderer> Stage<'a, Message, Theme, Renderer> {
+    pub(crate) fn new(
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+        zoom: f32,
+        pan: Vector,
+        on_change: impl Fn(CanvasMessage) -> Message + 'a,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            zoom,
+            pan,
+            padding: 0.0,
+            fill_width: true,
+            background: CanvasBackground::Theme,
+            theme_background: Color::TRANSPARENT,
+            layout_overlay: LayoutOverlay::Off,
+            rulers: false,
+            grid: None,
+            measure: false,
+            guide_colors: GuideColors::default(),
+            on_change: Box::new(on_change),
+        }
+    }
+
+    pub(crate) fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Lay the content out at its own width (framed stories) instead of the stage width
+    pub(crate) fn framed(mut self, framed: bool) -> Self {
+        self.fill_width = !framed;
+        self
+    }
+
+    /// Draw the story on a background; `theme_background` is the theme's color
+    pub(crate) fn background(
+        mut self,
+        background: CanvasBackground,
+        theme_background: Color,
+    ) -> Self {
+        self.background = background;
+        self.theme_background = theme_background;
+        self
+    }
+
+    pub(crate) fn layout_overlay(mut self, layout_overlay: LayoutOverlay) -> Self {
+        self.layout_overlay = layout_overlay;
+        self
+    }
+
+    /// Rulers along the top and left edges
+    pub(crate) fn rulers(mut self, rulers: bool) -> Self {
+        self.rulers = rulers;
+        self
+    }
+
+    /// Grid over the story, every `spacing` story pixels
+    pub(crate) fn grid(mut self, spacing: Option<f32>) -> Self {
+        self.grid = spacing;
+        self
+    }
+
+    /// Measure mode: dragging shows the distance between two points
+    pub(crate) fn measure(mut self, measure: bool) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    /// Colors of the frame outline and rulers
+    pub(crate) fn guide_colors(mut self, colors: GuideColors) -> Self {
+        self.guide_colors = colors;
+        self
+    }
+
+    /// Size left for the content inside the padding
+    fn available(&self, bounds: Rectangle) -> Size {
+        Size::new(
+            (bounds.width - 2.0 * self.padding).max(0.0),
+            (bounds.height - 2.0 * self.padding).max(0.0),
+        )
+    }
+
+    /// Pan limited so the content can't be dragged out of sight: content larger
+    /// than the stage pans up to its edges, smaller content stays inside
+    fn clamped_pan(&self, pan: Vector, bounds: Rectangle, content: Size) -> Vector {
+        let available = self.available(bounds);
+        let clamp = |pan: f32, content: f32, available: f32| {
+            let slack = available - content * self.zoom;
+            pan.clamp(slack.min(0.0), slack.max(0.0))
+        };
+
+        Vector::new(
+            clamp(pan.x, content.width, available.width),
+            clamp(pan.y, content.height, available.height),
+        )
+    }
+
+    /// Transformation from the content's layout to the screen
+    fn transformation(&self, bounds: Rectangle, content: Size) -> Transformation {
+        let pan = self.clamped_pan(self.pan, bounds, content);
+
+        Transformation::translate(
+            bounds.x + self.padding + pan.x,
+            bounds.y + self.padding + pan.y,
+        ) * Transformation::scale(self.zoom)
+            * Transformation::translate(-bounds.x, -bounds.y)
+    }
+}
+
+impl<Message, Theme, Renderer> Stage<'_, Message, Theme, Renderer>
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    fn draw_background(&self, renderer: &mut Renderer, bounds: Rectangle, clip: Rectangle) {
+        let fill = |renderer: &mut Renderer, bounds, color| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    ..renderer::Quad::default()
+                },
+                color,
+            );
+        };
+
+        let color = match self.background {
+            CanvasBackground::Theme => self.theme_background,
+            CanvasBackground::White => Color::WHITE,
+            CanvasBackground::Black => Color::BLACK,
+            CanvasBackground::Custom(color) => color,
+            CanvasBackground::Checkerboard => {
+                fill(renderer, clip, CHECKER_LIGHT);
+
+                // Dark squares, aligned to the stage and limited to the visible part
+                let columns = (bounds.width / CHECKER_SIZE).ceil() as usize;
+                let rows = (bounds.height / CHECKER_SIZE).ceil() as usize;
+                for row in 0..rows {
+                    for column in (row % 2..columns).step_by(2) {
+                        let square = Rectangle::new(
+                            Point::new(
+                                bounds.x + column as f32 * CHECKER_SIZE,
+                                bounds.y + row as f32 * CHECKER_SIZE,
+                            ),
+                            Size::new(CHECKER_SIZE, CHECKER_SIZE),
+                        );
+                        if let Some(square) = square.intersection(&clip) {
+                            fill(renderer, square, CHECKER_DARK);
+                        }
+                    }
+                }
+                return;
+            }
+        };
+
+        fill(renderer, clip, color);
+    }
+
+    /// Outline a node of the story's layout and its descendants, in story coordinates
+    fn draw_layout(&self, renderer: &mut Renderer, layout: Layout<'_>, depth: usize) {
+        let bounds = layout.bounds();
+        let children: Vec<Rectangle> = layout.children().map(|child| child.bounds()).collect();
+        let fill = |renderer: &mut Renderer, bounds: Rectangle, color| {
+            if bounds.width > 0.01 && bounds.height > 0.01 {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds,
+                        ..renderer::Quad::default()
+                    },
+                    color,
+                );
+            }
+        };
+
+        if self.layout_overlay == LayoutOverlay::Spacing {
+            if let Some(first) = children.first() {
+                let inner = children
+                    .iter()
+                    .fold(*first, |union, child| union.union(child));
+
+                // Around the children
+                let strips = [
+                    Rectangle::new(
+                        bounds.position(),
+                        Size::new(bounds.width, inner.y - bounds.y),
+                    ),
+                    Rectangle::new(
+                        Point::new(bounds.x, inner.y + inner.height),
+                        Size::new(
+                            bounds.width,
+                            bounds.y + bounds.height - inner.y - inner.height,
+                        ),
+                    ),
+                    Rectangle::new(
+                        Point::new(bounds.x, inner.y),
+                        Size::new(inner.x - bounds.x, inner.height),
+                    ),
+                    Rectangle::new(
+                        Point::new(inner.x + inner.width, inner.y),
+                        Size::new(
+                            bounds.x + bounds.width - inner.x - inner.width,
+                            inner.height,
+                        ),
+                    ),
+                ];
+                for strip in strips {
+                    fill(renderer, strip, PADDING_COLOR);
+                }
+
+                // Between consecutive children, in a row or a column
+                for pair in children.windows(2) {
+                    let (a, b) = (pair[0], pair[1]);
+                    let gap = if b.x >= a.x + a.width {
+                        Rectangle::new(
+                            Point::new(a.x + a.width, inner.y),
+                            Size::new(b.x - a.x - a.width, inner.height),
+                        )
+                    } else if b.y >= a.y + a.height {
+                        Rectangle::new(
+                            Point::new(inner.x, a.y + a.height),
+                            Size::new(inner.width, b.y - a.y - a.height),
+                        )
+                    } else {
+                        continue;
+                    };
+                    fill(renderer, gap, SPACING_COLOR);
+                }
+            }
+        }
+
+        // A hairline at any zoom level
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border: iced::Border {
+                    color: OUTLINE_COLORS[depth % OUTLINE_COLORS.len()],
+                    width: 1.0 / self.zoom,
+                    radius: 0.0.into(),
+                },
+                ..renderer::Quad::default()
+            },
+            Color::TRANSPARENT,
+        );
+
+        for child in layout.children() {
+            self.draw_layout(renderer, child, depth + 1);
+        }
+    }
+}
+
+/// Scrolled distance in lines and pixels
+fn scroll_amount(delta: mouse::ScrollDelta) -> (Vector, Vector) {
+    match delta {
+        mouse::ScrollDelta::Lines { x, y } => (
+            Vector::new(x, y),
+            Vector::new(x * PAN_PER_LINE, y * PAN_PER_LINE),
+        ),
+        mouse::ScrollDelta::Pixels { x, y } => (
+            Vector::new(x / PIXELS_PER_LINE, y / PIXELS_PER_LINE),
+            Vector::new(x, y),
+        ),
+    }
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for Stage<'_, Message, Theme, Renderer>
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fill, Length::Fill)
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let size = limits.max();
+        let available = self.available(Rectangle::with_size(size));
+        let max_width = if self.fill_width {
+            available.width / self.zoom
+        } else {
+            f32::INFINITY
+        };
+
+        let content = self.content.as_widget_mut().layout(
+            &mut tree.children[0],
+            renderer,
+            &layout::Limits::new(Size::ZERO, Size::new(max_width, f32::INFINITY)),
+        );
+
+        layout::Node::with_children(size, vec![content])
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let content_layout = layout.children().next().expect("stage content");
+        let content_size = content_layout.bounds().size();
+        let transformation = self.transformation(bounds, content_size);
+        let pan = self.clamped_pan(self.pan, bounds, content_size);
+
+        let state = tree.state.downcast_mut::<State>();
+
+        // Report the available size, used to fit the story to the window
+        let available = self.available(bounds);
+        if state.reported != Some(available) {
+            state.reported = Some(available);
+            shell.publish((self.on_change)(CanvasMessage::StageResized(available)));
+        }
+
+        // Measure mode takes the left mouse button from the story
+        let clip = bounds.intersection(viewport).unwrap_or_default();
+        if self.measure {
+            let to_story = |position: Point| {
+                let point = position * transformation.inverse();
+                let origin = content_layout.position();
+                Point::new((point.x - origin.x).round(), (point.y - origin.y).round())
+            };
+
+            match event {
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                    if let Some(position) = cursor.position_over(clip) {
+                        let point = to_story(position);
+                        state.measurement = Some((point, point));
+                        state.measuring = true;
+                        shell.capture_event();
+                        shell.request_redraw();
+                        return;
+                    }
+                }
+                Event::Mouse(mouse::Event::CursorMoved { position }) if state.measuring => {
+                    if let Some((start, _)) = state.measurement {
+                        state.measurement = Some((start, to_story(*position)));
+                    }
+                    shell.capture_event();
+                    shell.request_redraw();
+                    return;
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                    if state.measuring =>
+                {
+                    state.measuring = false;
+                    shell.capture_event();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = *modifiers;
+            }
+            // Command + scroll zooms around the cursor
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if state.modifiers.command() => {
+                if let Some(position) = cursor.position_in(bounds) {
+                    let (lines, _) = scroll_amount(*delta);
+                    let zoom = (self.zoom * ZOOM_PER_LINE.powf(lines.y)).clamp(MIN_ZOOM, MAX_ZOOM);
+                    shell.publish((self.on_change)(CanvasMessage::ZoomAround {
+                        zoom,
+                        anchor: position - Vector::new(self.padding, self.padding),
+                        pan,
+                    }));
+                    shell.capture_event();
+                }
+                return;
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let Some((start, start_pan)) = state.drag {
+                    let panned =
+                        self.clamped_pan(start_pan + (*position - start), bounds, content_size);
+                    shell.publish((self.on_change)(CanvasMessage::Pan(panned)));
+                    shell.capture_event();
+                    return;
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(
+                mouse::Button::Left | mouse::Button::Middle,
+            )) if state.drag.is_some() => {
+                state.drag = None;
+                shell.capture_event();
+                return;
+            }
+            _ => {}
+        }
+
+        // The content sees the cursor in its own (unzoomed) coordinates
+        let content_cursor = if cursor.is_over(clip) {
+            cursor * transformation.inverse()
+        } else {
+            mouse::Cursor::Unavailable
+        };
+        self.content.as_widget_mut().update(
+            &mut tree.children[0],
+            event,
+            content_layout,
+            content_cursor,
+            renderer,
+            clipboard,
+            shell,
+            &(clip * transformation.inverse()),
+        );
+
+        if shell.is_event_captured() {
+            return;
+        }
+
+        match event {
+            // Dragging where the story doesn't handle the press pans the canvas
+            Event::Mouse(mouse::Event::ButtonPressed(
+                mouse::Button::Left | mouse::Button::Middle,
+            )) => {
+                if let Some(position) = cursor.position_over(clip) {
+                    state.drag = Some((position, pan));
+                    shell.capture_event();
+                }
+            }
+            // Scrolling the story where it doesn't scroll itself pans the canvas
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if cursor.is_over(clip) => {
+                let (_, pixels) = scroll_amount(*delta);
+                let pixels = if state.modifiers.shift() && pixels.x == 0.0 {
+                    Vector::new(pixels.y, 0.0)
+                } else {
+                    pixels
+                };
+                let panned = self.clamped_pan(pan + pixels, bounds, content_size);
+                if panned != pan {
+                    shell.publish((self.on_change)(CanvasMessage::Pan(panned)));
+                    shell.capture_event();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let Some(clip) = bounds.intersection(viewport) else {
+            return;
+        };
+        let content_layout = layout.children().next().expect("stage content");
+        let transformation = self.transformation(bounds, content_layout.bounds().size());
+        let inverse = transformation.inverse();
+        let content_cursor = if cursor.is_over(clip) {
+            cursor * inverse
+        } else {
+            mouse::Cursor::Unavailable
+        };
+
+        let content_bounds = content_layout.bounds();
+        let origin = content_bounds.position() * transformation;
+
+        renderer.with_layer(clip, |renderer| {
+            self.draw_background(renderer, bounds, clip);
+
+            if !self.fill_width {
+                guides::draw_frame(
+                    renderer,
+                    content_bounds * transformation,
+                    self.guide_colors.divider,
+                );
+            }
+
+            renderer.with_transformation(transformation, |renderer| {
+                self.content.as_widget().draw(
+                    &tree.children[0],
+                    renderer,
+                    theme,
+                    style,
+                    content_layout,
+                    content_cursor,
+                    &(clip * inverse),
+                );
+
+                if self.layout_overlay != LayoutOverlay::Off {
+                    self.draw_layout(renderer, content_layout, 0);
+                }
+
+                if let Some(spacing) = self.grid {
+                    if let Some(area) = content_bounds.intersection(&(clip * inverse)) {
+                        guides::draw_grid(
+                            renderer,
+                            area,
+                            content_bounds.position(),
+                            spacing,
+                            self.zoom,
+                        );
+                    }
+                }
+            });
+
+            if self.measure {
+                let state = tree.state.downcast_ref::<State>();
+                if let Some((start, end)) = state.measurement {
+                    guides::draw_measurement(renderer, start, end, origin, self.zoom, clip);
+                }
+            }
+
+            if self.rulers {
+                guides::draw_rulers(renderer, bounds, origin, self.zoom, self.guide_colors);
+            }
+        });
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        if state.drag.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+
+        let bounds = layout.bounds();
+        let clip = bounds.intersection(viewport).unwrap_or_default();
+        if !cursor.is_over(clip) {
+            return mouse::Interaction::None;
+        }
+        if self.measure {
+            return mouse::Interaction::Crosshair;
+        }
+
+        let content_layout = layout.children().next().expect("stage content");
+        let inverse = self
+            .transformation(bounds, content_layout.bounds().size())
+            .inverse();
+
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            content_layout,
+            cursor * inverse,
+            &(clip * inverse),
+            renderer,
+        )
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        let content_layout = layout.children().next().expect("stage content");
+        self.content.as_widget_mut().operate(
+            &mut tree.children[0],
+            content_layout,
+            renderer,
+            operation,
+        );
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &Renderer,
+        viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let content_layout = layout.children().next()?;
+        let transformation = Transformation::translate(translation.x, translation.y)
+            * self.transformation(layout.bounds(), content_layout.bounds().size());
+
+        // Overlays of the story (e.g. pick list menus) are zoomed along with it
+        let overlay = self.content.as_widget_mut().overlay(
+            &mut tree.children[0],
+            content_layout,
+            renderer,
+            &(*viewport * transformation.inverse()),
+            Vector::ZERO,
+        )?;
+
+        Some(overlay::Element::new(Box::new(TransformedOverlay {
+            overlay,
+            transformation,
+            node: layout::Node::default(),
+        })))
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<Stage<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: text::Renderer<Font = Font> + 'a,
+{
+    fn from(stage: Stage<'a, Message, Theme, Renderer>) -> Self {
+        Element::new(stage)
+    }
+}
+
+/// An overlay of the story, drawn with the stage's zoom and pan
+struct TransformedOverlay<'a, Message, Theme, Renderer> {
+    overlay: overlay::Element<'a, Message, Theme, Renderer>,
+    transformation: Transformation,
+    /// Layout of the overlay in the story's coordinates
+    node: layout::Node,
+}
+
+impl<Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for TransformedOverlay<'_, Message, Theme, Renderer>
+where
+    Renderer: iced::advanced::Renderer,
+{
+    fn layout(&mut self, renderer: &Renderer, bounds: Size) -> layout::Node {
+        let scale = self.transformation.scale_factor();
+        self.node = self.overlay.as_overlay_mut().layout(
+            renderer,
+            Size::new(bounds.width / scale, bounds.height / scale),
+        );
+
+        let bounds = self.node.bounds() * self.transformation;
+        layout::Node::new(bounds.size()).move_to(bounds.position())
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        _layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        renderer.with_transformation(self.transformation, |renderer| {
+            self.overlay.as_overlay().draw(
+                renderer,
+                theme,
+                style,
+                Layout::new(&self.node),
+                cursor * self.transformation.inverse(),
+            );
+        });
+    }
+
+    fn update(
+        &mut self,
+        event: &Event,
+        _layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        self.overlay.as_overlay_mut().update(
+            event,
+            Layout::new(&self.node),
+            cursor * self.transformation.inverse(),
+            renderer,
+            clipboard,
+            shell,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        _layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.overlay.as_overlay().mouse_interaction(
+            Layout::new(&self.node),
+            cursor * self.transformation.inverse(),
+            renderer,
+        )
+    }
+
+    fn index(&self) -> f32 {
+        self.overlay.as_overlay().index()
+    }
+}
@@ -0,0 +1,282 @@
+//! Launch options from command-line arguments and environment variables (native only)
+//!
+//! Supported options (arguments take precedence over environment variables):
+//!
+//! | Argument             | Environment variable | Example          |
+//! |----------------------|----------------------|------------------|
+//! | `--story <id>`       | `ICEBOOK_STORY`      | `buttons`        |
+//! | `--theme <id>`       | `ICEBOOK_THEME`      | `dark`, `system` |
+//! | `--size <WxH>`       | `ICEBOOK_SIZE`       | `1280x800`       |
+//! | `--toolbar <values>` | `ICEBOOK_TOOLBAR`    | `density:compact` |
+//! | `--list`             |                      |                  |
+//!
+//! Unknown arguments are ignored, so consumers can parse their own flags too.
+//! Empty values count as unset.
+
+use iced::Size;
+use once_cell::sync::Lazy;
+
+use crate::story::StoryMeta;
+use crate::theme::ThemeMode;
+
+const STORY_ENV: &str = "ICEBOOK_STORY";
+const THEME_ENV: &str = "ICEBOOK_THEME";
+const SIZE_ENV: &str = "ICEBOOK_SIZE";
+const TOOLBAR_ENV: &str = "ICEBOOK_TOOLBAR";
+
+/// Options parsed from the process arguments and environment
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// Story to open on launch
+    pub story: Option<String>,
+    /// Theme mode to start with ("system" or a theme id)
+    pub theme_mode: Option<ThemeMode>,
+    /// Initial window size
+    pub window_size: Option<Size>,
+    /// Values of the consumer's toolbar items (`id:value` pairs, comma-separated)
+    pub toolbar: Option<String>,
+    /// Print the registered stories and exit
+    pub list: bool,
+}
+
+static LAUNCH_OPTIONS: Lazy<LaunchOptions> =
+    Lazy::new(|| LaunchOptions::parse(std::env::args().skip(1), |key| std::env::var(key).ok()));
+
+/// Get the launch options of this process
+pub fn launch_options() -> &'static LaunchOptions {
+    &LAUNCH_OPTIONS
+}
+
+impl LaunchOptions {
+    /// Parse options from arguments, falling back to environment variables
+    ///
+    /// Invalid values are logged as warnings and ignored.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut options = Self::default();
+        let mut story = None;
+        let mut theme = None;
+        let mut size = None;
+        let mut toolbar = None;
+
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            // Accept both `--flag value` and `--flag=value`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+
+            let target = match flag.as_str() {
+                "--list" => {
+                    options.list = true;
+                    continue;
+                }
+                "--story" => &mut story,
+                "--theme" => &mut theme,
+                "--size" => &mut size,
+                "--toolbar" => &mut toolbar,
+                _ => continue,
+            };
+
+            // A following flag is not a value (`--story --list`)
+            let value = inline_value.or_else(|| args.next_if(|next| !next.starts_with("--")));
+            match value {
+                Some(value) => *target = Some(value),
+                None => tracing::warn!("Missing value for {flag}"),
+            }
+        }
+
+        let non_empty = |value: &String| !value.is_empty();
+        let env = |key| env(key).filter(non_empty);
+
+        options.story = story.filter(non_empty).or_else(|| env(STORY_ENV));
+        options.toolbar = toolbar.filter(non_empty).or_else(|| env(TOOLBAR_ENV));
+
+        // Theme ids are validated against the provider's themes when resolved
+        options.theme_mode = theme
+            .filter(non_empty)
+            .or_else(|| env(THEME_ENV))
+            .map(|theme| ThemeMode::from_id(&theme));
+
+        options.window_size = size
+            .filter(non_empty)
+            .or_else(|| env(SIZE_ENV))
+            .and_then(|value| {
+                let size = parse_size(&value);
+                if size.is_none() {
+                    tracing::warn!(
+                        "Invalid size \"{value}\" (expected WIDTHxHEIGHT, e.g. 1280x800)"
+                    );
+                }
+                size
+            });
+
+        options
+    }
+}
+
+/// Print the registered stories, one per line: `id<TAB>category<TAB>title`
+pub fn print_stories(stories: &[StoryMeta]) {
+    for story in stories {
+        println!("{}\t{}\t{}", story.id, story.category, story.title);
+    }
+}
+
+fn parse_size(value: &str) -> Option<Size> {
+    let (width, height) = value.split_once(['x', 'X'])?;
+    let width: f32 = width.trim().parse().ok()?;
+    let height: f32 = height.trim().parse().ok()?;
+
+    let valid = |length: f32| length.is_finite() && length > 0.0;
+    (valid(width) && valid(height)).then(|| Size::new(width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    fn env(key: &str) -> Option<String> {
+        match key {
+            STORY_ENV => Some("inputs".to_string()),
+            THEME_ENV => Some("dark".to_string()),
+            SIZE_ENV => Some("800x600".to_string()),
+            TOOLBAR_ENV => Some("density:compact".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn parses_flags() {
+        let options = LaunchOptions::parse(
+            args(&[
+                "--story",
+                "buttons",
+                "--theme",
+                "ocean",
+                "--size",
+                "1280x800",
+                "--toolbar",
+                "locale:de,rtl:true",
+            ]),
+            no_env,
+        );
+        assert_eq!(options.story.as_deref(), Some("buttons"));
+        assert_eq!(options.toolbar.as_deref(), Some("locale:de,rtl:true"));
+        assert_eq!(
+            options.theme_mode,
+            Some(ThemeMode::Named("ocean".to_string()))
+        );
+        assert_eq!(options.window_size, Some(Size::new(1280.0, 800.0)));
+        assert!(!options.list);
+    }
+
+    #[test]
+    fn parses_inline_values() {
+        let options = LaunchOptions::parse(
+            args(&[
+                "--story=buttons",
+                "--theme=System",
+                "--size=640X480",
+                "--list",
+            ]),
+            no_env,
+        );
+        assert_eq!(options.story.as_deref(), Some("buttons"));
+        assert_eq!(options.theme_mode, Some(ThemeMode::System));
+        assert_eq!(options.window_size, Some(Size::new(640.0, 480.0)));
+        assert!(options.list);
+    }
+
+    #[test]
+    fn falls_back_to_env() {
+        let options = LaunchOptions::parse(args(&[]), env);
+        assert_eq!(options.story.as_deref(), Some("inputs"));
+        assert_eq!(
+            options.theme_mode,
+            Some(ThemeMode::Named("dark".to_string()))
+        );
+        assert_eq!(options.window_size, Some(Size::new(800.0, 600.0)));
+        assert_eq!(options.toolbar.as_deref(), Some("density:compact"));
+    }
+
+    #[test]
+    fn flags_take_precedence_over_env() {
+        let options = LaunchOptions::parse(
+            args(&[
+                "--story",
+                "buttons",
+                "--theme",
+                "light",
+                "--size",
+                "300x200",
+                "--toolbar=density:comfortable",
+            ]),
+            env,
+        );
+        assert_eq!(options.toolbar.as_deref(), Some("density:comfortable"));
+        assert_eq!(options.story.as_deref(), Some("buttons"));
+        assert_eq!(
+            options.theme_mode,
+            Some(ThemeMode::Named("light".to_string()))
+        );
+        assert_eq!(options.window_size, Some(Size::new(300.0, 200.0)));
+    }
+
+    #[test]
+    fn empty_values_are_unset() {
+        let options = LaunchOptions::parse(args(&["--story=", "--theme="]), no_env);
+        assert_eq!(options.story, None);
+        assert_eq!(options.theme_mode, None);
+
+        let options = LaunchOptions::parse(args(&["--story=", "--size", ""]), env);
+        assert_eq!(options.story.as_deref(), Some("inputs"));
+        assert_eq!(options.window_size, Some(Size::new(800.0, 600.0)));
+
+        let options = LaunchOptions::parse(args(&[]), |_| Some(String::new()));
+        assert_eq!(options.story, None);
+        assert_eq!(options.theme_mode, None);
+        assert_eq!(options.window_size, None);
+    }
+
+    #[test]
+    fn missing_values_are_ignored() {
+        let options = LaunchOptions::parse(args(&["--story", "--list", "--theme"]), no_env);
+        assert_eq!(options.story, None);
+        assert_eq!(options.theme_mode, None);
+        assert!(options.list);
+    }
+
+    #[test]
+    fn ignores_unknown_flags() {
+        let options = LaunchOptions::parse(
+            args(&["--verbose", "--port", "8080", "--story", "buttons", "extra"]),
+            no_env,
+        );
+        assert_eq!(options.story.as_deref(), Some("buttons"));
+        assert_eq!(options.window_size, None);
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        for size in [
+            "", "1280", "1280x", "x800", "0x800", "1280x-1", "wide", "infx800", "NaNx1",
+        ] {
+            assert_eq!(parse_size(size), None, "{size:?}");
+        }
+        assert_eq!(parse_size(" 1280 x 800 "), Some(Size::new(1280.0, 800.0)));
+
+        let options = LaunchOptions::parse(args(&["--size", "big"]), env);
+        assert_eq!(options.window_size, None);
+    }
+}
@@ -0,0 +1,502 @@
+//! Sidebar themes loaded from JSON or TOML
+//!
+//! Teams that only want to re-skin the chrome can describe a `SidebarTheme`
+//! in a data file instead of implementing the trait:
+//!
+//! ```toml
+//! sidebar_background = "#141414"
+//! text_primary = "#f2f2f2"
+//! text_secondary = "#bfbfbf"
+//! selected_background = "#ffffff1a"
+//! hover_background = "#ffffff0d"
+//! content_background = "#262626"
+//!
+//! # Optional typography (defaults match `SidebarTheme`)
+//! title_size = 24
+//! nav_font = { family = "Fira Sans", shaping = "advanced" }
+//! sidebar_width = 240
+//! ```
+//!
+//! The color keys match the `sidebar` section of the theme editor's export.
+
+use iced::widget::text::Shaping;
+use iced::{Color, Font};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+
+use crate::fonts::intern;
+use crate::theme::{
+    SidebarColors, SidebarFont, SidebarTheme, DEFAULT_BUTTON_SIZE, DEFAULT_NAV_SIZE,
+    DEFAULT_SECTION_SIZE, DEFAULT_SIDEBAR_WIDTH, DEFAULT_TITLE_SIZE,
+};
+use crate::tokens::parse_hex_color;
+
+/// A `SidebarTheme` described by data (JSON or TOML)
+///
+/// Load it once, e.g. in a `Lazy` static, and return it from
+/// `ThemeProvider::get_sidebar_theme`:
+///
+/// ```rust,ignore
+/// static SIDEBAR: Lazy<DataSidebarTheme> = Lazy::new(|| {
+///     DataSidebarTheme::from_toml(include_str!("sidebar.toml")).expect("valid sidebar theme")
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataSidebarTheme {
+    pub sidebar_background: Color,
+    pub text_primary: Color,
+    pub text_secondary: Color,
+    pub selected_background: Color,
+    pub hover_background: Color,
+    pub content_background: Color,
+    pub title_font: SidebarFont,
+    pub title_size: f32,
+    pub section_font: SidebarFont,
+    pub section_size: f32,
+    pub nav_font: SidebarFont,
+    pub nav_size: f32,
+    pub button_font: SidebarFont,
+    pub button_size: f32,
+    pub sidebar_width: f32,
+}
+
+/// Error loading a `DataSidebarTheme`
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataThemeError {
+    /// The file could not be read
+    Io(String),
+    /// The source is not valid JSON/TOML, or has missing or unknown keys
+    Parse(String),
+    /// A color is not a valid hex color
+    InvalidColor { key: &'static str, value: String },
+    /// A font shaping is not "basic" or "advanced"
+    InvalidShaping { key: &'static str, value: String },
+    /// A size is negative or not finite
+    InvalidSize { key: &'static str, value: f32 },
+    /// The file extension is not `.json` or `.toml`
+    UnknownFormat(String),
+}
+
+impl std::fmt::Display for DataThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataThemeError::Io(error) => write!(f, "could not read sidebar theme: {error}"),
+            DataThemeError::Parse(error) => write!(f, "invalid sidebar theme: {error}"),
+            DataThemeError::InvalidColor { key, value } => write!(
+                f,
+                "invalid color \"{value}\" for `{key}` (expected #rgb, #rrggbb or #rrggbbaa)"
+            ),
+            DataThemeError::InvalidShaping { key, value } => write!(
+                f,
+                "invalid shaping \"{value}\" for `{key}` (expected \"basic\" or \"advanced\")"
+            ),
+            DataThemeError::InvalidSize { key, value } => {
+                write!(
+                    f,
+                    "invalid size {value} for `{key}` (expected a positive number)"
+                )
+            }
+            DataThemeError::UnknownFormat(path) => {
+                write!(
+                    f,
+                    "unknown sidebar theme format for \"{path}\" (expected .json or .toml)"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataThemeError {}
+
+impl DataSidebarTheme {
+    /// A sidebar theme with the given colors and the default fonts and sizes
+    pub fn from_colors(colors: SidebarColors) -> Self {
+        Self {
+            sidebar_background: colors.sidebar_background,
+            text_primary: colors.text_primary,
+            text_secondary: colors.text_secondary,
+            selected_background: colors.selected_background,
+            hover_background: colors.hover_background,
+            content_background: colors.content_background,
+            title_font: SidebarFont::default(),
+            title_size: DEFAULT_TITLE_SIZE,
+            section_font: SidebarFont::default(),
+            section_size: DEFAULT_SECTION_SIZE,
+            nav_font: SidebarFont::default(),
+            nav_size: DEFAULT_NAV_SIZE,
+            button_font: SidebarFont::default(),
+            button_size: DEFAULT_BUTTON_SIZE,
+            sidebar_width: DEFAULT_SIDEBAR_WIDTH,
+        }
+    }
+
+    /// Parse a sidebar theme from JSON
+    pub fn from_json(source: &str) -> Result<Self, DataThemeError> {
+        serde_json::from_str::<RawSidebarTheme>(source)
+            .map_err(|error| DataThemeError::Parse(error.to_string()))?
+            .validate()
+    }
+
+    /// Parse a sidebar theme from TOML
+    pub fn from_toml(source: &str) -> Result<Self, DataThemeError> {
+        toml::from_str::<RawSidebarTheme>(source)
+            .map_err(|error| DataThemeError::Parse(error.to_string()))?
+            .validate()
+    }
+
+    /// Read a sidebar theme from a `.json` or `.toml` file (native only)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, DataThemeError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|error| DataThemeError::Io(format!("{}: {error}", path.display())))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&source),
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::from_toml(&source),
+            _ => Err(DataThemeError::UnknownFormat(path.display().to_string())),
+        }
+    }
+}
+
+impl SidebarTheme for DataSidebarTheme {
+    fn sidebar_background(&self) -> Color {
+        self.sidebar_background
+    }
+    fn text_primary(&self) -> Color {
+        self.text_primary
+    }
+    fn text_secondary(&self) -> Color {
+        self.text_secondary
+    }
+    fn selected_background(&self) -> Color {
+        self.selected_background
+    }
+    fn hover_background(&self) -> Color {
+        self.hover_background
+    }
+    fn content_background(&self) -> Color {
+        self.content_background
+    }
+
+    fn title_font(&self) -> SidebarFont {
+        self.title_font
+    }
+    fn title_size(&self) -> f32 {
+        self.title_size
+    }
+    fn section_font(&self) -> SidebarFont {
+        self.section_font
+    }
+    fn section_size(&self) -> f32 {
+        self.section_size
+    }
+    fn nav_font(&self) -> SidebarFont {
+        self.nav_font
+    }
+    fn nav_size(&self) -> f32 {
+        self.nav_size
+    }
+    fn button_font(&self) -> SidebarFont {
+        self.button_font
+    }
+    fn button_size(&self) -> f32 {
+        self.button_size
+    }
+    fn sidebar_width(&self) -> f32 {
+        self.sidebar_width
+    }
+}
+
+/// Sidebar theme as written in the data file (strings not yet validated)
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSidebarTheme {
+    sidebar_background: String,
+    text_primary: String,
+    text_secondary: String,
+    selected_background: String,
+    hover_background: String,
+    content_background: String,
+    title_font: Option<RawFont>,
+    title_size: Option<f32>,
+    section_font: Option<RawFont>,
+    section_size: Option<f32>,
+    nav_font: Option<RawFont>,
+    nav_size: Option<f32>,
+    button_font: Option<RawFont>,
+    button_size: Option<f32>,
+    sidebar_width: Option<f32>,
+}
+
+/// A font: a family name, or a table with `family` and `shaping`
+enum RawFont {
+    Family(String),
+    Spec(RawFontSpec),
+}
+
+impl<'de> Deserialize<'de> for RawFont {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawFontVisitor;
+
+        impl<'de> Visitor<'de> for RawFontVisitor {
+            type Value = RawFont;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a font family name or a table with `family` and `shaping`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<RawFont, E> {
+                Ok(RawFont::Family(value.to_string()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<RawFont, A::Error> {
+                RawFontSpec::deserialize(MapAccessDeserializer::new(map)).map(RawFont::Spec)
+            }
+        }
+
+        deserializer.deserialize_any(RawFontVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFontSpec {
+    family: Option<String>,
+    shaping: Option<String>,
+}
+
+impl RawSidebarTheme {
+    fn validate(self) -> Result<DataSidebarTheme, DataThemeError> {
+        Ok(DataSidebarTheme {
+            sidebar_background: color("sidebar_background", &self.sidebar_background)?,
+            text_primary: color("text_primary", &self.text_primary)?,
+            text_secondary: color("text_secondary", &self.text_secondary)?,
+            selected_background: color("selected_background", &self.selected_background)?,
+            hover_background: color("hover_background", &self.hover_background)?,
+            content_background: color("content_background", &self.content_background)?,
+            title_font: font("title_font", self.title_font)?,
+            title_size: size("title_size", self.title_size, DEFAULT_TITLE_SIZE)?,
+            section_font: font("section_font", self.section_font)?,
+            section_size: size("section_size", self.section_size, DEFAULT_SECTION_SIZE)?,
+            nav_font: font("nav_font", self.nav_font)?,
+            nav_size: size("nav_size", self.nav_size, DEFAULT_NAV_SIZE)?,
+            button_font: font("button_font", self.button_font)?,
+            button_size: size("button_size", self.button_size, DEFAULT_BUTTON_SIZE)?,
+            sidebar_width: size("sidebar_width", self.sidebar_width, DEFAULT_SIDEBAR_WIDTH)?,
+        })
+    }
+}
+
+fn color(key: &'static str, value: &str) -> Result<Color, DataThemeError> {
+    parse_hex_color(value).ok_or_else(|| DataThemeError::InvalidColor {
+        key,
+        value: value.to_string(),
+    })
+}
+
+fn size(key: &'static str, value: Option<f32>, default: f32) -> Result<f32, DataThemeError> {
+    match value {
+        None => Ok(default),
+        Some(value) if value.is_finite() && value > 0.0 => Ok(value),
+        Some(value) => Err(DataThemeError::InvalidSize { key, value }),
+    }
+}
+
+/// Convert a font spec, interning the family name as `Font` needs a `&'static str`
+fn font(key: &'static str, value: Option<RawFont>) -> Result<SidebarFont, DataThemeError> {
+    let (family, shaping) = match value {
+        None => return Ok(SidebarFont::default()),
+        Some(RawFont::Family(family)) => (Some(family), None),
+        Some(RawFont::Spec(RawFontSpec { family, shaping })) => (family, shaping),
+    };
+
+    let shaping = match shaping.as_deref() {
+        None => SidebarFont::default().shaping,
+        Some(value) if value.eq_ignore_ascii_case("basic") => Shaping::Basic,
+        Some(value) if value.eq_ignore_ascii_case("advanced") => Shaping::Advanced,
+        Some(value) => {
+            return Err(DataThemeError::InvalidShaping {
+                key,
+                value: value.to_string(),
+            })
+        }
+    };
+    let font = match family {
+        Some(family) => Font::with_name(intern(family)),
+        None => Font::DEFAULT,
+    };
+
+    Ok(SidebarFont { font, shaping })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORS: &str = r##"
+        sidebar_background = "#141414"
+        text_primary = "#f2f2f2"
+        text_secondary = "#bfbfbf"
+        selected_background = "#ffffff1a"
+        hover_background = "#ffffff0d"
+        content_background = "#262626"
+    "##;
+
+    fn toml(extra: &str) -> Result<DataSidebarTheme, DataThemeError> {
+        DataSidebarTheme::from_toml(&format!("{COLORS}\n{extra}"))
+    }
+
+    #[test]
+    fn missing_keys_use_the_defaults() {
+        let theme = toml("").unwrap();
+
+        assert_eq!(theme.sidebar_background, Color::from_rgb8(0x14, 0x14, 0x14));
+        assert_eq!(theme.title_size, DEFAULT_TITLE_SIZE);
+        assert_eq!(theme.sidebar_width, DEFAULT_SIDEBAR_WIDTH);
+        assert_eq!(theme.nav_font, SidebarFont::default());
+    }
+
+    #[test]
+    fn parses_fonts_as_names_or_tables() {
+        let theme = toml(
+            r#"
+            title_font = "Fira Sans"
+            nav_font = { family = "Fira Mono", shaping = "Advanced" }
+            button_font = { shaping = "basic" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(theme.title_font.font, Font::with_name("Fira Sans"));
+        assert_eq!(theme.nav_font.font, Font::with_name("Fira Mono"));
+        assert_eq!(theme.nav_font.shaping, Shaping::Advanced);
+        assert_eq!(theme.button_font.font, Font::DEFAULT);
+    }
+
+    #[test]
+    fn family_names_are_interned() {
+        let name = |theme: DataSidebarTheme| match theme.title_font.font.family {
+            iced::font::Family::Name(name) => name,
+            family => panic!("unexpected family {family:?}"),
+        };
+        let first = name(toml(r#"title_font = "Interned Sans""#).unwrap());
+        let second = name(toml(r#"title_font = "Interned Sans""#).unwrap());
+
+        assert!(std::ptr::eq(first, second));
+    }
+
+    #[test]
+    fn parses_json() {
+        let theme = DataSidebarTheme::from_json(
+            r##"{
+                "sidebar_background": "#141414",
+                "text_primary": "#f2f2f2",
+                "text_secondary": "#bfbfbf",
+                "selected_background": "#ffffff1a",
+                "hover_background": "#ffffff0d",
+                "content_background": "#262626",
+                "nav_font": { "family": "Fira Sans" },
+                "nav_size": 16
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            theme,
+            toml("nav_font = \"Fira Sans\"\nnav_size = 16").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_colors() {
+        let error = DataSidebarTheme::from_toml(&COLORS.replace("#262626", "#26262g")).unwrap_err();
+
+        assert_eq!(
+            error,
+            DataThemeError::InvalidColor {
+                key: "content_background",
+                value: "#26262g".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        assert_eq!(
+            toml("nav_size = -1").unwrap_err(),
+            DataThemeError::InvalidSize {
+                key: "nav_size",
+                value: -1.0,
+            }
+        );
+        assert_eq!(
+            toml("sidebar_width = 0").unwrap_err(),
+            DataThemeError::InvalidSize {
+                key: "sidebar_width",
+                value: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_shaping() {
+        assert_eq!(
+            toml(r#"title_font = { shaping = "fancy" }"#).unwrap_err(),
+            DataThemeError::InvalidShaping {
+                key: "title_font",
+                value: "fancy".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_malformed_fonts() {
+        let Err(DataThemeError::Parse(error)) = toml("nav_font = 3") else {
+            panic!("expected a parse error");
+        };
+        assert!(error.contains("nav_font"), "{error}");
+        assert!(error.contains("a font family name or a table"), "{error}");
+
+        let Err(DataThemeError::Parse(error)) = toml(r#"nav_font = { size = 3 }"#) else {
+            panic!("expected a parse error");
+        };
+        assert!(error.contains("unknown field `size`"), "{error}");
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_keys() {
+        let Err(DataThemeError::Parse(error)) =
+            DataSidebarTheme::from_toml(r##"sidebar_background = "#141414""##)
+        else {
+            panic!("expected a parse error");
+        };
+        assert!(error.contains("missing field"), "{error}");
+
+        let Err(DataThemeError::Parse(error)) = toml("nav_colour = 3") else {
+            panic!("expected a parse error");
+        };
+        assert!(error.contains("unknown field `nav_colour`"), "{error}");
+
+        assert!(matches!(
+            DataSidebarTheme::from_json("{"),
+            Err(DataThemeError::Parse(_))
+        ));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn rejects_unknown_file_formats() {
+        let path = std::env::temp_dir().join("icebook-sidebar-theme.yaml");
+        std::fs::write(&path, COLORS).unwrap();
+        let result = DataSidebarTheme::from_file(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(result, Err(DataThemeError::UnknownFormat(_))));
+        assert!(matches!(
+            DataSidebarTheme::from_file("/nonexistent/sidebar.toml"),
+            Err(DataThemeError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,700 @@
+//! Live theme editor
+//!
+//! A panel next to the story that edits the current theme's sidebar colors,
+//! Iced palette and design tokens. Edits apply to the running storybook
+//! immediately and can be exported as JSON, TOML or a Rust snippet to paste
+//! back into the theme provider.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+use iced::theme::Palette;
+use iced::widget::{
+    button, column, container, row, scrollable, slider, text, text_input, Column, Row, Space,
+};
+use iced::{Color, Element, Font, Length, Task};
+use serde::Serialize;
+
+use crate::sidebar::chrome_button;
+use crate::theme::{RecoloredSidebarTheme, SidebarColors, SidebarTheme, ThemeInfo, ThemeProvider};
+use crate::tokens::{
+    color_hex, parse_hex_color, serialize_hex, ColorRole, DesignToken, TokenValue,
+};
+
+/// Width of the editor panel
+const EDITOR_WIDTH: f32 = 320.0;
+
+/// Largest value of a numeric token, in logical pixels
+const MAX_TOKEN_PIXELS: f32 = 4096.0;
+
+/// Iced palette color names, in the order of `palette_colors`
+const PALETTE_NAMES: [&str; 6] = [
+    "background",
+    "text",
+    "primary",
+    "success",
+    "warning",
+    "danger",
+];
+
+/// Export format of the edited theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Toml,
+    Rust,
+}
+
+/// An editable value in the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditorField {
+    /// Sidebar color, by index in `SidebarColors::NAMES`
+    Sidebar(usize),
+    /// Iced palette color, by index in `PALETTE_NAMES`
+    Palette(usize),
+    /// Design token, by index in the theme's token list
+    Token(usize),
+}
+
+/// Messages from the theme editor panel
+#[derive(Debug, Clone)]
+pub enum EditorMessage {
+    /// Show or hide the RGBA sliders of a color
+    ToggleExpanded(EditorField),
+    /// Set a color (from the sliders)
+    SetColor(EditorField, Color),
+    /// Text of a hex or numeric field changed
+    InputChanged(EditorField, String),
+    /// Discard all edits of the current theme
+    Reset,
+    /// Change the export format
+    SetExportFormat(ExportFormat),
+    /// Copy the export to the clipboard
+    CopyExport,
+}
+
+/// The edited values of one theme
+pub struct ThemeEdits {
+    pub sidebar: RecoloredSidebarTheme,
+    pub palette: Palette,
+    pub tokens: Vec<DesignToken>,
+}
+
+impl ThemeEdits {
+    /// Start from the provider's values for a theme
+    pub fn from_provider<P: ThemeProvider>(theme: &ThemeInfo) -> Self {
+        let base = P::get_named_sidebar_theme(theme);
+
+        Self {
+            sidebar: RecoloredSidebarTheme {
+                base,
+                colors: SidebarColors::from_theme(base),
+            },
+            palette: P::get_iced_theme(theme).palette(),
+            tokens: P::design_tokens(theme),
+        }
+    }
+
+    /// The Iced theme built from the edited palette
+    pub fn iced_theme(&self, theme: &ThemeInfo) -> iced::Theme {
+        iced::Theme::custom(theme.label, self.palette)
+    }
+
+    fn color(&self, field: EditorField) -> Option<Color> {
+        match field {
+            EditorField::Sidebar(i) => self.sidebar.colors.to_array().get(i).copied(),
+            EditorField::Palette(i) => palette_colors(&self.palette).get(i).copied(),
+            EditorField::Token(i) => match self.tokens.get(i)?.value {
+                TokenValue::Color(color, _) => Some(color),
+                _ => None,
+            },
+        }
+    }
+
+    fn set_color(&mut self, field: EditorField, color: Color) {
+        let target = match field {
+            EditorField::Sidebar(i) => self.sidebar.colors.get_mut(i),
+            EditorField::Palette(i) => palette_color_mut(&mut self.palette, i),
+            EditorField::Token(i) => match self.tokens.get_mut(i).map(|token| &mut token.value) {
+                Some(TokenValue::Color(value, _)) => Some(value),
+                _ => None,
+            },
+        };
+        if let Some(target) = target {
+            *target = color;
+        }
+    }
+
+    fn set_dimension(&mut self, index: usize, pixels: f32) {
+        if let Some(token) = self.tokens.get_mut(index) {
+            match &mut token.value {
+                TokenValue::Spacing(value)
+                | TokenValue::Radius(value)
+                | TokenValue::FontSize(value) => *value = pixels,
+                TokenValue::Color(..) => {}
+            }
+        }
+    }
+}
+
+/// State of the theme editor: edits per theme and the panel's UI state
+#[derive(Default)]
+pub struct ThemeEditor {
+    /// Whether the panel is shown
+    pub open: bool,
+    /// Edited values by theme id (themes without edits use the provider's values)
+    edits: BTreeMap<&'static str, ThemeEdits>,
+    /// Color whose sliders are shown
+    expanded: Option<EditorField>,
+    /// Text of hex/numeric fields while being typed (may not parse yet), by theme id
+    drafts: HashMap<(&'static str, EditorField), String>,
+    format: ExportFormat,
+}
+
+impl ThemeEditor {
+    /// Edited values of a theme, if it was edited
+    pub fn edits(&self, theme: &ThemeInfo) -> Option<&ThemeEdits> {
+        self.edits.get(theme.id)
+    }
+
+    /// Handle a panel message for the given theme
+    ///
+    /// `base` provides the theme's original values the first time it is edited
+    /// (or exported); only changed values record edits.
+    pub fn update<M: 'static>(
+        &mut self,
+        message: EditorMessage,
+        theme: &ThemeInfo,
+        base: impl FnOnce() -> ThemeEdits,
+    ) -> Task<M> {
+        match message {
+            EditorMessage::ToggleExpanded(field) => {
+                self.expanded = (self.expanded != Some(field)).then_some(field);
+            }
+            EditorMessage::SetColor(field, color) => {
+                let edits = self.edits.entry(theme.id).or_insert_with(base);
+                edits.set_color(field, color);
+                self.drafts.remove(&(theme.id, field));
+            }
+            EditorMessage::InputChanged(field, value) => {
+                let edits = self.edits.entry(theme.id).or_insert_with(base);
+                match (field, edits.color(field)) {
+                    (_, Some(_)) => {
+                        if let Some(color) = parse_hex_color(&value) {
+                            edits.set_color(field, color);
+                        }
+                    }
+                    (EditorField::Token(i), None) => {
+                        if let Some(pixels) = parse_pixels(&value) {
+                            edits.set_dimension(i, pixels);
+                        }
+                    }
+                    _ => {}
+                }
+                self.drafts.insert((theme.id, field), value);
+            }
+            EditorMessage::Reset => {
+                self.edits.remove(theme.id);
+                self.drafts.retain(|(id, _), _| *id != theme.id);
+                self.expanded = None;
+            }
+            EditorMessage::SetExportFormat(format) => self.format = format,
+            EditorMessage::CopyExport => {
+                let export = match self.edits.get(theme.id) {
+                    Some(edits) => export(theme, edits, self.format),
+                    None => export(theme, &base(), self.format),
+                };
+                return iced::clipboard::write(export);
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Parse a numeric token value (logical pixels, finite and in range)
+fn parse_pixels(value: &str) -> Option<f32> {
+    let pixels = value.trim().parse::<f32>().ok()?;
+    (0.0..=MAX_TOKEN_PIXELS).contains(&pixels).then_some(pixels)
+}
+
+/// Render the editor panel for a theme's current values
+pub fn editor_view<'a>(
+    editor: &'a ThemeEditor,
+    info: &ThemeInfo,
+    edits: &ThemeEdits,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, EditorMessage> {
+    let text_color = theme.text_primary();
+    let text_secondary = theme.text_secondary();
+    let bg_color = theme.sidebar_background();
+    let preview_bg = theme.hover_background();
+
+    let section = |title: &'static str| {
+        column![
+            Space::new().height(8),
+            text(title).size(theme.section_size()).color(text_secondary)
+        ]
+    };
+
+    let draft = |field| editor.drafts.get(&(info.id, field));
+    let color_field = |field, name: &str, color| {
+        color_field(
+            draft(field),
+            editor.expanded == Some(field),
+            field,
+            name,
+            color,
+            theme,
+        )
+    };
+
+    let mut content = Column::new()
+        .spacing(8)
+        .push(
+            text(format!("Edit {}", info.label))
+                .size(20)
+                .color(text_color),
+        )
+        .push(chrome_button("Reset", false, EditorMessage::Reset, theme));
+
+    content = content.push(section("SIDEBAR"));
+    for (i, (name, color)) in SidebarColors::NAMES
+        .iter()
+        .zip(edits.sidebar.colors.to_array())
+        .enumerate()
+    {
+        content = content.push(color_field(EditorField::Sidebar(i), name, color));
+    }
+
+    content = content.push(section("PALETTE"));
+    for (i, (name, color)) in PALETTE_NAMES
+        .iter()
+        .zip(palette_colors(&edits.palette))
+        .enumerate()
+    {
+        content = content.push(color_field(EditorField::Palette(i), name, color));
+    }
+
+    if !edits.tokens.is_empty() {
+        content = content.push(section("TOKENS"));
+        for (i, token) in edits.tokens.iter().enumerate() {
+            let field = EditorField::Token(i);
+            content = content.push(match token.value {
+                TokenValue::Color(color, _) => color_field(field, &token.name, color),
+                TokenValue::Spacing(pixels)
+                | TokenValue::Radius(pixels)
+                | TokenValue::FontSize(pixels) => {
+                    number_field(draft(field), field, &token.name, pixels, theme)
+                }
+            });
+        }
+    }
+
+    // Export: format picker, copy button and a preview of the output
+    let formats = [
+        ("JSON", ExportFormat::Json),
+        ("TOML", ExportFormat::Toml),
+        ("Rust", ExportFormat::Rust),
+    ]
+    .map(|(label, format)| {
+        chrome_button(
+            label,
+            editor.format == format,
+            EditorMessage::SetExportFormat(format),
+            theme,
+        )
+    });
+    let preview = container(
+        text(export(info, edits, editor.format))
+            .size(11)
+            .font(Font::MONOSPACE)
+            .color(text_color),
+    )
+    .padding(8)
+    .width(Length::Fill)
+    .style(move |_| container::Style {
+        background: Some(iced::Background::Color(preview_bg)),
+        border: iced::Border::default().rounded(4),
+        ..Default::default()
+    });
+
+    content = content
+        .push(section("EXPORT"))
+        .push(Row::with_children(formats).spacing(4))
+        .push(chrome_button(
+            "Copy to Clipboard",
+            false,
+            EditorMessage::CopyExport,
+            theme,
+        ))
+        .push(preview);
+
+    let content = scrollable(content.padding(16))
+        .height(Length::Fill)
+        .style(move |_, status| theme.scrollbar_style(status));
+
+    container(content)
+        .width(Length::Fixed(EDITOR_WIDTH))
+        .height(Length::Fill)
+        .style(move |_| container::Style {
+            background: Some(iced::Background::Color(bg_color)),
+            ..Default::default()
+        })
+        .into()
+}
+
+/// A color row: swatch (toggles the sliders), name and hex input
+fn color_field<'a>(
+    draft: Option<&String>,
+    expanded: bool,
+    field: EditorField,
+    name: &str,
+    color: Color,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, EditorMessage> {
+    let text_color = theme.text_primary();
+    let border_color = theme.text_secondary();
+
+    let swatch = button(Space::new().width(20).height(20))
+        .on_press(EditorMessage::ToggleExpanded(field))
+        .padding(0)
+        .style(move |_, _| button::Style {
+            background: Some(iced::Background::Color(color)),
+            border: iced::Border {
+                color: border_color,
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..Default::default()
+        });
+
+    let value = draft.cloned().unwrap_or_else(|| color_hex(color));
+    let hex = text_input("#rrggbb", &value)
+        .on_input(move |value| EditorMessage::InputChanged(field, value))
+        .size(12)
+        .padding(4)
+        .width(96)
+        .style(move |_, status| theme.input_style(status));
+
+    let header = row![
+        swatch,
+        text(name.to_string())
+            .size(12)
+            .color(text_color)
+            .width(Length::Fill),
+        hex,
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center);
+
+    if !expanded {
+        return header.into();
+    }
+
+    // RGBA sliders
+    let channels = color.into_rgba8();
+    let sliders = ["R", "G", "B", "A"].into_iter().enumerate().fold(
+        Column::new().spacing(4),
+        |sliders, (channel, label)| {
+            sliders.push(
+                row![
+                    text(label).size(12).color(text_color).width(16),
+                    slider(0..=255u8, channels[channel], move |value| {
+                        let mut channels = channels;
+                        channels[channel] = value;
+                        let [r, g, b, a] = channels;
+                        EditorMessage::SetColor(
+                            field,
+                            Color::from_rgba8(r, g, b, f32::from(a) / 255.0),
+                        )
+                    }),
+                    text(channels[channel].to_string())
+                        .size(12)
+                        .color(text_color)
+                        .width(28),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            )
+        },
+    );
+
+    column![header, sliders].spacing(8).into()
+}
+
+/// A numeric row for dimension tokens (logical pixels)
+fn number_field<'a>(
+    draft: Option<&String>,
+    field: EditorField,
+    name: &str,
+    pixels: f32,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, EditorMessage> {
+    let value = draft.cloned().unwrap_or_else(|| pixels.to_string());
+
+    row![
+        text(name.to_string())
+            .size(12)
+            .color(theme.text_primary())
+            .width(Length::Fill),
+        text_input("px", &value)
+            .on_input(move |value| EditorMessage::InputChanged(field, value))
+            .size(12)
+            .padding(4)
+            .width(96)
+            .style(move |_, status| theme.input_style(status)),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+fn palette_colors(palette: &Palette) -> [Color; 6] {
+    [
+        palette.background,
+        palette.text,
+        palette.primary,
+        palette.success,
+        palette.warning,
+        palette.danger,
+    ]
+}
+
+fn palette_color_mut(palette: &mut Palette, index: usize) -> Option<&mut Color> {
+    match index {
+        0 => Some(&mut palette.background),
+        1 => Some(&mut palette.text),
+        2 => Some(&mut palette.primary),
+        3 => Some(&mut palette.success),
+        4 => Some(&mut palette.warning),
+        5 => Some(&mut palette.danger),
+        _ => None,
+    }
+}
+
+/// Serialized form of an edited theme (JSON and TOML exports)
+#[derive(Serialize)]
+struct ThemeExport<'a> {
+    sidebar: SidebarColors,
+    palette: PaletteExport,
+    #[serde(serialize_with = "serialize_tokens")]
+    tokens: &'a [DesignToken],
+}
+
+#[derive(Serialize)]
+struct PaletteExport {
+    #[serde(serialize_with = "serialize_hex")]
+    background: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    text: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    primary: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    success: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    warning: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    danger: Color,
+}
+
+/// Tokens as a `name = value` map (hex strings for colors, pixels otherwise)
+fn serialize_tokens<S: serde::Serializer>(
+    tokens: &&[DesignToken],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    #[serde(untagged)]
+    enum Value {
+        Color(String),
+        Pixels(f32),
+    }
+
+    serializer.collect_map(tokens.iter().map(|token| {
+        let value = match token.value {
+            TokenValue::Color(color, _) => Value::Color(color_hex(color)),
+            TokenValue::Spacing(pixels)
+            | TokenValue::Radius(pixels)
+            | TokenValue::FontSize(pixels) => Value::Pixels(pixels),
+        };
+        (token.name.as_str(), value)
+    }))
+}
+
+/// Export the edited values of a theme in the given format
+pub fn export(theme: &ThemeInfo, edits: &ThemeEdits, format: ExportFormat) -> String {
+    let palette = &edits.palette;
+    let data = ThemeExport {
+        sidebar: edits.sidebar.colors,
+        palette: PaletteExport {
+            background: palette.background,
+            text: palette.text,
+            primary: palette.primary,
+            success: palette.success,
+            warning: palette.warning,
+            danger: palette.danger,
+        },
+        tokens: &edits.tokens,
+    };
+
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&data).unwrap_or_else(|e| e.to_string()),
+        ExportFormat::Toml => toml::to_string_pretty(&data).unwrap_or_else(|e| e.to_string()),
+        ExportFormat::Rust => rust_snippet(theme, edits),
+    }
+}
+
+/// Rust source for the edited values, to paste into the theme provider
+fn rust_snippet(theme: &ThemeInfo, edits: &ThemeEdits) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "// SidebarTheme colors ({})", theme.label);
+    for (name, color) in SidebarColors::NAMES
+        .iter()
+        .zip(edits.sidebar.colors.to_array())
+    {
+        let _ = writeln!(
+            out,
+            "fn {name}(&self) -> Color {{\n    {}\n}}",
+            rust_color(color)
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "\n// ThemeProvider::get_iced_theme\niced::theme::Palette {{"
+    );
+    for (name, color) in PALETTE_NAMES.iter().zip(palette_colors(&edits.palette)) {
+        let _ = writeln!(out, "    {name}: {},", rust_color(color));
+    }
+    out.push_str("}\n");
+
+    if !edits.tokens.is_empty() {
+        out.push_str("\n// ThemeProvider::design_tokens\nvec![\n");
+        for token in &edits.tokens {
+            let (constructor, value) = match token.value {
+                TokenValue::Color(color, ColorRole::Text) => ("text_color", rust_color(color)),
+                TokenValue::Color(color, ColorRole::Background) => {
+                    ("background_color", rust_color(color))
+                }
+                TokenValue::Color(color, ColorRole::Other) => ("color", rust_color(color)),
+                TokenValue::Spacing(pixels) => ("spacing", format!("{pixels:?}")),
+                TokenValue::Radius(pixels) => ("radius", format!("{pixels:?}")),
+                TokenValue::FontSize(pixels) => ("font_size", format!("{pixels:?}")),
+            };
+            let _ = writeln!(
+                out,
+                "    DesignToken::{constructor}({:?}, {value}),",
+                token.name
+            );
+        }
+        out.push_str("]\n");
+    }
+
+    out
+}
+
+fn rust_color(color: Color) -> String {
+    match color.into_rgba8() {
+        [r, g, b, 255] => format!("Color::from_rgb8({r}, {g}, {b})"),
+        [r, g, b, a] => format!(
+            "Color::from_rgba8({r}, {g}, {b}, {:.2})",
+            f32::from(a) / 255.0
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{default_sidebar_theme, Brightness};
+
+    fn base() -> ThemeEdits {
+        let sidebar = default_sidebar_theme(Brightness::Light);
+
+        ThemeEdits {
+            sidebar: RecoloredSidebarTheme {
+                base: sidebar,
+                colors: SidebarColors::from_theme(sidebar),
+            },
+            palette: iced::Theme::Light.palette(),
+            tokens: vec![
+                DesignToken::color("color.accent", Color::BLACK),
+                DesignToken::spacing("spacing.md", 8.0),
+            ],
+        }
+    }
+
+    fn send(editor: &mut ThemeEditor, message: EditorMessage) {
+        let _: Task<()> = editor.update(message, &ThemeInfo::LIGHT, base);
+    }
+
+    fn spacing(editor: &ThemeEditor) -> Option<f32> {
+        match editor.edits(&ThemeInfo::LIGHT)?.tokens[1].value {
+            TokenValue::Spacing(pixels) => Some(pixels),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn panel_state_records_no_edits() {
+        let mut editor = ThemeEditor::default();
+
+        send(
+            &mut editor,
+            EditorMessage::ToggleExpanded(EditorField::Palette(0)),
+        );
+        send(
+            &mut editor,
+            EditorMessage::SetExportFormat(ExportFormat::Rust),
+        );
+        send(&mut editor, EditorMessage::CopyExport);
+
+        assert!(editor.edits(&ThemeInfo::LIGHT).is_none());
+    }
+
+    #[test]
+    fn changes_record_edits() {
+        let mut editor = ThemeEditor::default();
+
+        send(
+            &mut editor,
+            EditorMessage::SetColor(EditorField::Palette(2), Color::WHITE),
+        );
+        let edits = editor.edits(&ThemeInfo::LIGHT).unwrap();
+        assert_eq!(edits.palette.primary, Color::WHITE);
+
+        send(&mut editor, EditorMessage::Reset);
+        assert!(editor.edits(&ThemeInfo::LIGHT).is_none());
+
+        send(
+            &mut editor,
+            EditorMessage::InputChanged(EditorField::Token(0), "#ff0000".to_string()),
+        );
+        let edits = editor.edits(&ThemeInfo::LIGHT).unwrap();
+        assert_eq!(
+            edits.color(EditorField::Token(0)),
+            Some(Color::from_rgb(1.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn numeric_tokens_take_finite_pixels_in_range() {
+        let mut editor = ThemeEditor::default();
+        let mut input = |value: &str| {
+            send(
+                &mut editor,
+                EditorMessage::InputChanged(EditorField::Token(1), value.to_string()),
+            );
+            spacing(&editor)
+        };
+
+        assert_eq!(input(" 12.5 "), Some(12.5));
+        for rejected in ["inf", "NaN", "1e39", "-1", "5000", "12px"] {
+            assert_eq!(input(rejected), Some(12.5), "{rejected}");
+        }
+        assert_eq!(input("0"), Some(0.0));
+        assert_eq!(input("4096"), Some(MAX_TOKEN_PIXELS));
+    }
+}
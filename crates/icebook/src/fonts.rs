@@ -0,0 +1,502 @@
+//! Font registry and the generated "Fonts" story
+//!
+//! Fonts passed in `Settings::fonts` are parsed before they are loaded, so
+//! invalid data is reported instead of silently ignored. The registry records
+//! the family, weight and style of every face, and icebook adds a "Fonts" story
+//! with a specimen of each family and a glyph-coverage check of sample strings.
+
+use std::sync::Mutex;
+
+use iced::font::{Family, Style, Weight};
+use iced::widget::{column, row, text, Column, Space};
+use iced::{Element, Font};
+use once_cell::sync::Lazy;
+
+use crate::story::StoryMeta;
+use crate::theme::SidebarTheme;
+use crate::{FALLBACK_FONT, FALLBACK_FONT_NAME};
+
+/// Story id of the generated fonts page
+pub const FONTS_STORY_ID: &str = "fonts";
+
+/// Metadata of the generated fonts page
+pub const FONTS_STORY: StoryMeta = StoryMeta::new(FONTS_STORY_ID, "Fonts", "Theme");
+
+/// Sample strings checked for glyph coverage: (language, sample)
+pub const DEFAULT_FONT_SAMPLES: &[(&str, &str)] = &[
+    ("English", "The quick brown fox jumps over the lazy dog"),
+    (
+        "German",
+        "Falsches Üben von Xylophonmusik quält jeden größeren Zwerg",
+    ),
+    (
+        "French",
+        "Voix ambiguë d'un cœur qui au zéphyr préfère les jattes de kiwis",
+    ),
+    (
+        "Spanish",
+        "El pingüino Wenceslao hizo kilómetros bajo exhaustiva lluvia y frío",
+    ),
+    ("Polish", "Pchnąć w tę łódź jeża lub ośm skrzyń fig"),
+    ("Turkish", "Pijamalı hasta yağız şoföre çabucak güvendi"),
+    (
+        "Greek",
+        "Ταχίστη αλώπηξ βαφής ψημένη γη, δρασκελίζει υπέρ νωθρού κυνός",
+    ),
+    (
+        "Russian",
+        "Съешь же ещё этих мягких французских булок, да выпей чаю",
+    ),
+    ("Japanese", "いろはにほへと ちりぬるを"),
+    ("Chinese", "天地玄黄 宇宙洪荒"),
+];
+
+/// Font sizes of the specimen lines
+const SPECIMEN_SIZES: [f32; 6] = [12.0, 14.0, 16.0, 20.0, 24.0, 32.0];
+
+/// A font face loaded into the storybook
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontFace {
+    /// Family name, as used with `Font::with_name`
+    pub family: &'static str,
+    pub weight: Weight,
+    pub style: Style,
+    /// Font file the face was read from
+    pub data: &'static [u8],
+    /// Index of the face in the font file (non-zero only in font collections)
+    pub index: u32,
+}
+
+impl FontFace {
+    /// The iced `Font` selecting this face
+    pub fn font(&self) -> Font {
+        Font {
+            family: Family::Name(self.family),
+            weight: self.weight,
+            style: self.style,
+            ..Font::DEFAULT
+        }
+    }
+
+    /// Characters of `sample` this face has no glyph for (whitespace is ignored)
+    ///
+    /// Parses the face on every call; see `FontCoverage` for checking several
+    /// samples.
+    pub fn missing_glyphs(&self, sample: &str) -> Vec<char> {
+        missing_glyphs(self.parse().as_ref(), sample)
+    }
+
+    fn parse(&self) -> Option<ttf_parser::Face<'static>> {
+        ttf_parser::Face::parse(self.data, self.index).ok()
+    }
+
+    /// Weight and style, e.g. "Bold Italic (700)"
+    pub fn description(&self) -> String {
+        let (name, number) = match self.weight {
+            Weight::Thin => ("Thin", 100),
+            Weight::ExtraLight => ("Extra Light", 200),
+            Weight::Light => ("Light", 300),
+            Weight::Normal => ("Regular", 400),
+            Weight::Medium => ("Medium", 500),
+            Weight::Semibold => ("Semibold", 600),
+            Weight::Bold => ("Bold", 700),
+            Weight::ExtraBold => ("Extra Bold", 800),
+            Weight::Black => ("Black", 900),
+        };
+        let style = match self.style {
+            Style::Normal => "",
+            Style::Italic => " Italic",
+            Style::Oblique => " Oblique",
+        };
+
+        format!("{name}{style} ({number})")
+    }
+}
+
+/// A font in `Settings::fonts` that could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontError {
+    /// Position in `Settings::fonts`
+    pub index: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "font #{}: {}", self.index, self.message)
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// The fonts loaded into the storybook: the built-in fallback font followed by
+/// the faces of `Settings::fonts`
+#[derive(Debug, Clone)]
+pub struct FontRegistry {
+    faces: Vec<FontFace>,
+    /// Font files that parsed (excluding the fallback font)
+    files: Vec<&'static [u8]>,
+    errors: Vec<FontError>,
+}
+
+impl FontRegistry {
+    /// Parse font files; files that fail to parse are recorded in `errors`
+    pub fn new(fonts: &[&'static [u8]]) -> Self {
+        let mut registry = Self {
+            faces: Vec::new(),
+            files: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        if let Ok(faces) = parse_faces(FALLBACK_FONT) {
+            registry.add_faces(faces);
+        }
+
+        for (index, data) in fonts.iter().enumerate() {
+            match parse_faces(data) {
+                Ok(faces) => {
+                    registry.add_faces(faces);
+                    registry.files.push(data);
+                }
+                Err(message) => registry.errors.push(FontError { index, message }),
+            }
+        }
+
+        registry
+    }
+
+    /// Record faces, skipping those already registered (same family, weight and style)
+    fn add_faces(&mut self, faces: Vec<FontFace>) {
+        for face in faces {
+            let registered = self.faces.iter().any(|other| {
+                other.family == face.family
+                    && other.weight == face.weight
+                    && other.style == face.style
+            });
+            if !registered {
+                self.faces.push(face);
+            }
+        }
+    }
+
+    /// All faces, in registration order
+    pub fn faces(&self) -> &[FontFace] {
+        &self.faces
+    }
+
+    /// Family names, in registration order
+    pub fn families(&self) -> Vec<&'static str> {
+        let mut families: Vec<&'static str> = Vec::new();
+        for face in &self.faces {
+            if !families.contains(&face.family) {
+                families.push(face.family);
+            }
+        }
+        families
+    }
+
+    /// Faces of a family
+    pub fn family(&self, family: &str) -> impl Iterator<Item = &FontFace> {
+        let family = family.to_string();
+        self.faces.iter().filter(move |face| face.family == family)
+    }
+
+    /// Font files to load into iced (the fallback font is loaded separately)
+    pub fn files(&self) -> &[&'static [u8]] {
+        &self.files
+    }
+
+    /// Font files that could not be parsed
+    pub fn errors(&self) -> &[FontError] {
+        &self.errors
+    }
+}
+
+/// Parse every face of a font file (or font collection)
+fn parse_faces(data: &'static [u8]) -> Result<Vec<FontFace>, String> {
+    let count = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+
+    (0..count)
+        .map(|index| {
+            let face = ttf_parser::Face::parse(data, index).map_err(|error| error.to_string())?;
+            let family = family_name(&face).ok_or("the font has no family name")?;
+
+            Ok(FontFace {
+                family: intern(family),
+                weight: weight(face.weight()),
+                style: match face.style() {
+                    ttf_parser::Style::Normal => Style::Normal,
+                    ttf_parser::Style::Italic => Style::Italic,
+                    ttf_parser::Style::Oblique => Style::Oblique,
+                },
+                data,
+                index,
+            })
+        })
+        .collect()
+}
+
+/// Typographic family name, falling back to the legacy family name
+fn family_name(face: &ttf_parser::Face<'_>) -> Option<String> {
+    let name = |id| {
+        face.names()
+            .into_iter()
+            .filter(|name| name.name_id == id && name.is_unicode())
+            .find_map(|name| name.to_string())
+    };
+
+    name(ttf_parser::name_id::TYPOGRAPHIC_FAMILY).or_else(|| name(ttf_parser::name_id::FAMILY))
+}
+
+/// Nearest iced weight of an OpenType weight class
+fn weight(weight: ttf_parser::Weight) -> Weight {
+    match weight.to_number() {
+        0..=149 => Weight::Thin,
+        150..=249 => Weight::ExtraLight,
+        250..=349 => Weight::Light,
+        350..=449 => Weight::Normal,
+        450..=549 => Weight::Medium,
+        550..=649 => Weight::Semibold,
+        650..=749 => Weight::Bold,
+        750..=849 => Weight::ExtraBold,
+        _ => Weight::Black,
+    }
+}
+
+/// Characters of `sample` a parsed face has no glyph for, sorted (all of them
+/// when the face doesn't parse)
+fn missing_glyphs(face: Option<&ttf_parser::Face<'_>>, sample: &str) -> Vec<char> {
+    let mut missing: Vec<char> = sample
+        .chars()
+        .filter(|c| !c.is_whitespace() && face.is_none_or(|face| face.glyph_index(*c).is_none()))
+        .collect();
+    missing.sort_unstable();
+    missing.dedup();
+    missing
+}
+
+/// Specimen and glyph coverage of every family, for the fonts page
+///
+/// Computed once, parsing each specimen face once for all samples, as the page
+/// is rebuilt every frame during theme transitions and while the performance
+/// HUD is shown.
+#[derive(Debug, Clone)]
+pub(crate) struct FontCoverage {
+    families: Vec<FamilyCoverage>,
+}
+
+#[derive(Debug, Clone)]
+struct FamilyCoverage {
+    family: &'static str,
+    /// Face of the specimen: the regular upright face, or the family's first face
+    face: FontFace,
+    /// Weight and style of the family's faces, e.g. "Regular (400) · Bold (700)"
+    styles: String,
+    /// Specimen text: the first sample the face covers (or the first sample)
+    specimen: &'static str,
+    /// Characters missing from each sample: (language, sample, missing)
+    samples: Vec<(&'static str, &'static str, Vec<char>)>,
+}
+
+impl FontCoverage {
+    pub(crate) fn new(registry: &FontRegistry, samples: &[(&'static str, &'static str)]) -> Self {
+        let families = registry
+            .families()
+            .into_iter()
+            .map(|family| {
+                let faces: Vec<&FontFace> = registry.family(family).collect();
+                let face = faces
+                    .iter()
+                    .find(|face| face.weight == Weight::Normal && face.style == Style::Normal)
+                    .copied()
+                    .unwrap_or(faces[0]);
+
+                let parsed = face.parse();
+                let samples: Vec<(&'static str, &'static str, Vec<char>)> = samples
+                    .iter()
+                    .map(|(language, sample)| {
+                        (*language, *sample, missing_glyphs(parsed.as_ref(), sample))
+                    })
+                    .collect();
+                let specimen = samples
+                    .iter()
+                    .find(|(_, _, missing)| missing.is_empty())
+                    .or(samples.first())
+                    .map_or(DEFAULT_FONT_SAMPLES[0].1, |(_, sample, _)| *sample);
+
+                FamilyCoverage {
+                    family,
+                    face: *face,
+                    styles: faces
+                        .iter()
+                        .map(|face| face.description())
+                        .collect::<Vec<_>>()
+                        .join(" · "),
+                    specimen,
+                    samples,
+                }
+            })
+            .collect();
+
+        Self { families }
+    }
+}
+
+/// Family names are leaked once per name, as `Font` needs a `&'static str`
+pub(crate) fn intern(family: String) -> &'static str {
+    static FAMILIES: Lazy<Mutex<Vec<&'static str>>> = Lazy::new(Mutex::default);
+
+    let mut families = FAMILIES.lock().unwrap_or_else(|error| error.into_inner());
+    match families.iter().find(|name| **name == family) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(family.into_boxed_str());
+            families.push(name);
+            name
+        }
+    }
+}
+
+/// Render the generated fonts page
+pub(crate) fn fonts_view<'a, M: 'a>(
+    registry: &'a FontRegistry,
+    coverage: &'a FontCoverage,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, M> {
+    let text_color = theme.text_primary();
+    let text_secondary = theme.text_secondary();
+
+    let mut page = Column::new()
+        .spacing(16)
+        .push(text("Fonts").size(32).color(text_color))
+        .push(
+            text(format!(
+                "{} faces in {} families. Characters a font has no glyph for fall back to {}.",
+                registry.faces().len(),
+                coverage.families.len(),
+                FALLBACK_FONT_NAME
+            ))
+            .size(14)
+            .color(text_secondary),
+        );
+
+    for error in registry.errors() {
+        page = page.push(
+            text(format!("Not loaded: {error}"))
+                .size(14)
+                .color(text_color),
+        );
+    }
+
+    for family in &coverage.families {
+        let font = family.face.font();
+        let specimen = family.specimen;
+
+        page = page.push(column![
+            Space::new().height(8),
+            text(family.family).size(24).color(text_color).font(font),
+            text(&family.styles).size(13).color(text_secondary),
+        ]);
+
+        // Specimen at standard sizes
+        for size in SPECIMEN_SIZES {
+            page = page.push(
+                row![
+                    text(format!("{size}px"))
+                        .size(12)
+                        .color(text_secondary)
+                        .width(48),
+                    text(specimen)
+                        .size(size)
+                        .color(text_color)
+                        .font(font)
+                        .shaping(text::Shaping::Advanced),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        // Glyph coverage of the sample strings
+        page = page.push(text("Glyph coverage").size(16).color(text_color));
+        for (language, sample, missing) in &family.samples {
+            let status = if missing.is_empty() {
+                "Covered".to_string()
+            } else {
+                let code_points: Vec<String> = missing
+                    .iter()
+                    .take(4)
+                    .map(|c| format!("U+{:04X}", u32::from(*c)))
+                    .collect();
+                format!("Missing {}: {}", missing.len(), code_points.join(" "))
+            };
+
+            page = page.push(
+                row![
+                    text(*language).size(13).color(text_secondary).width(80),
+                    text(*sample)
+                        .size(14)
+                        .color(text_color)
+                        .font(font)
+                        .shaping(text::Shaping::Advanced)
+                        .width(iced::Length::Fill),
+                    text(status).size(13).color(text_secondary).width(200),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    page.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_the_fallback_font_once() {
+        let registry = FontRegistry::new(&[FALLBACK_FONT]);
+
+        assert_eq!(registry.families(), [FALLBACK_FONT_NAME]);
+        assert_eq!(registry.faces().len(), 1);
+        assert_eq!(registry.files().len(), 1);
+        assert!(registry.errors().is_empty());
+    }
+
+    #[test]
+    fn records_invalid_fonts() {
+        let registry = FontRegistry::new(&[b"not a font", FALLBACK_FONT]);
+
+        assert_eq!(registry.errors().len(), 1);
+        assert_eq!(registry.errors()[0].index, 0);
+        assert_eq!(registry.files(), [FALLBACK_FONT]);
+    }
+
+    #[test]
+    fn covers_samples_once_per_family() {
+        let registry = FontRegistry::new(&[]);
+        let coverage = FontCoverage::new(
+            &registry,
+            &[("Chinese", "天地"), ("English", "Fox"), ("Polish", "Łódź")],
+        );
+
+        let [family] = coverage.families.as_slice() else {
+            panic!("one family expected");
+        };
+        assert_eq!(family.family, FALLBACK_FONT_NAME);
+        assert_eq!(family.specimen, "Fox");
+        assert_eq!(family.samples[0].2, ['地', '天']);
+        assert!(family.samples[1].2.is_empty());
+    }
+
+    #[test]
+    fn finds_missing_glyphs() {
+        let registry = FontRegistry::new(&[]);
+        let face = registry.faces()[0];
+
+        assert!(face.missing_glyphs("The quick brown fox").is_empty());
+        assert_eq!(face.missing_glyphs("天 天地"), ['地', '天']);
+    }
+}
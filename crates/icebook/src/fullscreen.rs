@@ -0,0 +1,102 @@
+//! Fullscreen for focus mode: the browser Fullscreen API on WASM, the window's
+//! fullscreen mode on native
+
+use iced::{window, Subscription, Task};
+
+/// Enter or leave fullscreen with the whole page (WASM)
+///
+/// Browsers only allow this in response to user input, and may refuse it
+/// (e.g. in an iframe without `allowfullscreen`); focus mode still hides the
+/// chrome then.
+#[cfg(target_arch = "wasm32")]
+pub fn set_fullscreen<M: Send + 'static>(_window: Option<window::Id>, fullscreen: bool) -> Task<M> {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return Task::none();
+    };
+
+    if fullscreen {
+        if let Some(element) = document.document_element() {
+            let _ = element.request_fullscreen();
+        }
+    } else if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    }
+
+    Task::none()
+}
+
+/// Enter or leave fullscreen with the main window (native; the latest window
+/// when not running as a multi-window daemon)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_fullscreen<M: Send + 'static>(window: Option<window::Id>, fullscreen: bool) -> Task<M> {
+    let mode = if fullscreen {
+        window::Mode::Fullscreen
+    } else {
+        window::Mode::Windowed
+    };
+
+    match window {
+        Some(id) => window::set_mode(id, mode),
+        None => window::latest().and_then(move |id| window::set_mode(id, mode)),
+    }
+}
+
+/// Subscribe to the browser leaving fullscreen on its own (e.g. `Esc`)
+///
+/// Registers a `fullscreenchange` listener on the document, which is removed
+/// again when the subscription is dropped.
+#[cfg(target_arch = "wasm32")]
+pub fn fullscreen_exits() -> Subscription<()> {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    /// Keeps the listener alive and unregisters it on drop
+    struct Listener {
+        document: web_sys::Document,
+        callback: Closure<dyn FnMut(web_sys::Event)>,
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = self.document.remove_event_listener_with_callback(
+                "fullscreenchange",
+                self.callback.as_ref().unchecked_ref(),
+            );
+        }
+    }
+
+    Subscription::run(|| {
+        iced::stream::channel(4, async |mut output| {
+            let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+                return;
+            };
+
+            let watched = document.clone();
+            let callback =
+                Closure::<dyn FnMut(web_sys::Event)>::new(move |_event: web_sys::Event| {
+                    if watched.fullscreen_element().is_none() {
+                        let _ = output.try_send(());
+                    }
+                });
+
+            if document
+                .add_event_listener_with_callback(
+                    "fullscreenchange",
+                    callback.as_ref().unchecked_ref(),
+                )
+                .is_err()
+            {
+                return;
+            }
+
+            let _listener = Listener { document, callback };
+            iced::futures::future::pending::<()>().await;
+        })
+    })
+}
+
+/// Fullscreen is only left through icebook on native
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fullscreen_exits() -> Subscription<()> {
+    Subscription::none()
+}
@@ -0,0 +1,600 @@
+//! Virtual gamepad for stories
+//!
+//! The selected story subscribes to gamepad events through `StoryContext::gamepad`
+//! (stories in pop-out windows get none). With the gamepad turned on in the
+//! toolbar, events come from an on-screen controller panel below the canvas and
+//! from the keyboard (no key clashes with a storybook shortcut):
+//!
+//! | Input        | Keys           |
+//! |--------------|----------------|
+//! | D-pad        | Arrow keys     |
+//! | A / B        | `Z` / `X`      |
+//! | Shoulders    | `Q` / `E`      |
+//! | Left stick   | `W` `A` `S` `D`|
+//! | Right stick  | `U` `H` `J` `K`|
+//! | Menu         | `Enter`        |
+//!
+//! A device can feed events too (`Settings::gamepad`). All events go through
+//! `Message::GamepadInput`: the Menu button moves the input between the story
+//! and the sidebar, where the D-pad moves the focus, A activates and B returns
+//! to the story.
+
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use iced::futures::channel::mpsc;
+use iced::widget::{button, column, container, mouse_area, text, Row, Space};
+use iced::{keyboard, Alignment, Element, Length, Subscription, Vector};
+
+use crate::sidebar::divider;
+use crate::theme::SidebarTheme;
+
+/// A direction of the D-pad or a stick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Unit vector of the direction (y points down)
+    pub fn vector(self) -> Vector {
+        match self {
+            Direction::Up => Vector::new(0.0, -1.0),
+            Direction::Down => Vector::new(0.0, 1.0),
+            Direction::Left => Vector::new(-1.0, 0.0),
+            Direction::Right => Vector::new(1.0, 0.0),
+        }
+    }
+}
+
+/// A gamepad button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadButton {
+    DPad(Direction),
+    A,
+    B,
+    LeftShoulder,
+    RightShoulder,
+    /// Menu (Start) button; reserved by the storybook to move between the
+    /// sidebar and the story, never sent to stories
+    Menu,
+}
+
+/// An analog stick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+/// An event of the virtual gamepad
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    ButtonPressed(GamepadButton),
+    ButtonReleased(GamepadButton),
+    /// A stick moved; `position` is in -1.0..=1.0 on both axes (y points down),
+    /// and `Vector::ZERO` when centered
+    StickMoved {
+        stick: Stick,
+        position: Vector,
+    },
+}
+
+/// Events buffered per subscription before new ones are dropped
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Gamepad events of one storybook, delivered to the selected story's
+/// subscriptions
+///
+/// Subscriptions are identified by the channel's id, so each storybook (and each
+/// test) has its own.
+#[derive(Debug, Clone)]
+pub(crate) struct GamepadChannel {
+    id: u64,
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+#[derive(Debug, Default)]
+struct Subscribers {
+    /// Senders of the running subscriptions
+    senders: Vec<mpsc::Sender<GamepadEvent>>,
+    /// Events dropped because a subscription was not keeping up
+    dropped: u64,
+}
+
+impl GamepadChannel {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            subscribers: Arc::default(),
+        }
+    }
+
+    /// Subscribe to the channel's events
+    pub(crate) fn subscription(&self) -> Subscription<GamepadEvent> {
+        Subscription::run_with(self.clone(), |channel| {
+            let subscribers = channel.subscribers.clone();
+            iced::stream::channel(CHANNEL_CAPACITY, async move |output| {
+                lock(&subscribers).senders.push(output);
+                iced::futures::future::pending::<()>().await;
+            })
+        })
+    }
+
+    /// Send an event to every running subscription, forgetting the ones that ended
+    ///
+    /// Events for a subscription whose buffer is full are dropped and reported.
+    pub(crate) fn send(&self, event: GamepadEvent) {
+        let mut subscribers = lock(&self.subscribers);
+        let mut dropped = false;
+        subscribers
+            .senders
+            .retain_mut(|sender| match sender.try_send(event) {
+                Ok(()) => true,
+                Err(error) if error.is_full() => {
+                    dropped = true;
+                    true
+                }
+                Err(_) => false,
+            });
+
+        if dropped {
+            subscribers.dropped += 1;
+            // Report the first drop, then ever less often
+            if subscribers.dropped.is_power_of_two() {
+                tracing::warn!(
+                    "Dropped {} gamepad event(s): a story's gamepad subscription is not keeping up",
+                    subscribers.dropped
+                );
+            }
+        }
+    }
+
+    /// Subscribe a receiver buffering `capacity` events
+    #[cfg(test)]
+    pub(crate) fn subscribe(&self, capacity: usize) -> mpsc::Receiver<GamepadEvent> {
+        let (sender, receiver) = mpsc::channel(capacity);
+        lock(&self.subscribers).senders.push(sender);
+        receiver
+    }
+
+    /// Events dropped so far
+    #[cfg(test)]
+    fn dropped(&self) -> u64 {
+        lock(&self.subscribers).dropped
+    }
+}
+
+impl Hash for GamepadChannel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+fn lock(subscribers: &Mutex<Subscribers>) -> std::sync::MutexGuard<'_, Subscribers> {
+    subscribers
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+}
+
+/// A control of the virtual gamepad: a button, or one direction of a stick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadInput {
+    Button(GamepadButton),
+    Stick(Stick, Direction),
+}
+
+/// Messages from the controller panel and the keyboard mapping
+#[derive(Debug, Clone, Copy)]
+pub enum GamepadMessage {
+    /// Show or hide the controller panel (and the keyboard mapping)
+    Toggle,
+    Press(GamepadInput),
+    Release(GamepadInput),
+}
+
+/// State of the virtual gamepad
+#[derive(Debug, Default)]
+pub struct VirtualGamepad {
+    /// Panel shown and keyboard mapped
+    enabled: bool,
+    /// Buttons held down
+    buttons: BTreeSet<GamepadButton>,
+    /// Stick directions held down (left stick, right stick)
+    sticks: [BTreeSet<Direction>; 2],
+}
+
+impl VirtualGamepad {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Apply a message, returning the events it produced
+    pub fn update(&mut self, message: GamepadMessage) -> Vec<GamepadEvent> {
+        match message {
+            GamepadMessage::Toggle => {
+                self.enabled = !self.enabled;
+                if self.enabled {
+                    Vec::new()
+                } else {
+                    self.release_all()
+                }
+            }
+            GamepadMessage::Press(GamepadInput::Button(button)) => self
+                .buttons
+                .insert(button)
+                .then_some(GamepadEvent::ButtonPressed(button))
+                .into_iter()
+                .collect(),
+            GamepadMessage::Release(GamepadInput::Button(button)) => self
+                .buttons
+                .remove(&button)
+                .then_some(GamepadEvent::ButtonReleased(button))
+                .into_iter()
+                .collect(),
+            GamepadMessage::Press(GamepadInput::Stick(stick, direction)) => {
+                if self.sticks[stick as usize].insert(direction) {
+                    vec![self.stick_event(stick)]
+                } else {
+                    Vec::new()
+                }
+            }
+            GamepadMessage::Release(GamepadInput::Stick(stick, direction)) => {
+                if self.sticks[stick as usize].remove(&direction) {
+                    vec![self.stick_event(stick)]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    fn is_held(&self, input: GamepadInput) -> bool {
+        match input {
+            GamepadInput::Button(button) => self.buttons.contains(&button),
+            GamepadInput::Stick(stick, direction) => {
+                self.sticks[stick as usize].contains(&direction)
+            }
+        }
+    }
+
+    /// Release every button and center the sticks
+    fn release_all(&mut self) -> Vec<GamepadEvent> {
+        let mut events: Vec<GamepadEvent> = std::mem::take(&mut self.buttons)
+            .into_iter()
+            .map(GamepadEvent::ButtonReleased)
+            .collect();
+        for stick in [Stick::Left, Stick::Right] {
+            if !std::mem::take(&mut self.sticks[stick as usize]).is_empty() {
+                events.push(self.stick_event(stick));
+            }
+        }
+        events
+    }
+
+    /// Position of a stick from its held directions (diagonals have length 1)
+    fn stick_event(&self, stick: Stick) -> GamepadEvent {
+        let sum = self.sticks[stick as usize]
+            .iter()
+            .fold(Vector::ZERO, |sum, direction| sum + direction.vector());
+        let length = (sum.x * sum.x + sum.y * sum.y).sqrt();
+        let position = if length > 1.0 {
+            sum * (1.0 / length)
+        } else {
+            sum
+        };
+
+        GamepadEvent::StickMoved { stick, position }
+    }
+}
+
+/// Gamepad input mapped to a key (see the module documentation)
+pub fn key_input(key: &keyboard::Key) -> Option<GamepadInput> {
+    use keyboard::key::Named;
+
+    let input = match key.as_ref() {
+        keyboard::Key::Named(Named::ArrowUp) => {
+            GamepadInput::Button(GamepadButton::DPad(Direction::Up))
+        }
+        keyboard::Key::Named(Named::ArrowDown) => {
+            GamepadInput::Button(GamepadButton::DPad(Direction::Down))
+        }
+        keyboard::Key::Named(Named::ArrowLeft) => {
+            GamepadInput::Button(GamepadButton::DPad(Direction::Left))
+        }
+        keyboard::Key::Named(Named::ArrowRight) => {
+            GamepadInput::Button(GamepadButton::DPad(Direction::Right))
+        }
+        keyboard::Key::Named(Named::Enter) => GamepadInput::Button(GamepadButton::Menu),
+        keyboard::Key::Character(key) => match key.to_lowercase().as_str() {
+            "z" => GamepadInput::Button(GamepadButton::A),
+            "x" => GamepadInput::Button(GamepadButton::B),
+            "q" => GamepadInput::Button(GamepadButton::LeftShoulder),
+            "e" => GamepadInput::Button(GamepadButton::RightShoulder),
+            "w" => GamepadInput::Stick(Stick::Left, Direction::Up),
+            "s" => GamepadInput::Stick(Stick::Left, Direction::Down),
+            "a" => GamepadInput::Stick(Stick::Left, Direction::Left),
+            "d" => GamepadInput::Stick(Stick::Left, Direction::Right),
+            "u" => GamepadInput::Stick(Stick::Right, Direction::Up),
+            "j" => GamepadInput::Stick(Stick::Right, Direction::Down),
+            "h" => GamepadInput::Stick(Stick::Right, Direction::Left),
+            "k" => GamepadInput::Stick(Stick::Right, Direction::Right),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(input)
+}
+
+/// Render the on-screen controller panel shown below the canvas
+pub fn gamepad_panel<'a>(
+    gamepad: &VirtualGamepad,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, GamepadMessage> {
+    let key = |label: &'static str, input: GamepadInput| pad_key(label, input, gamepad, theme);
+
+    // A cross of four directions around an empty center
+    let cross = |title: &'static str, input: &dyn Fn(Direction) -> GamepadInput| {
+        let [up, down, left, right] = Direction::ALL.map(|direction| {
+            let label = match direction {
+                Direction::Up => "↑",
+                Direction::Down => "↓",
+                Direction::Left => "←",
+                Direction::Right => "→",
+            };
+            key(label, input(direction))
+        });
+
+        column![
+            text(title)
+                .size(theme.button_size())
+                .color(theme.text_secondary()),
+            column![
+                up,
+                Row::new()
+                    .spacing(4)
+                    .push(left)
+                    .push(Space::new().width(PAD_KEY_SIZE))
+                    .push(right),
+                down,
+            ]
+            .spacing(4)
+            .align_x(Alignment::Center),
+        ]
+        .spacing(6)
+        .align_x(Alignment::Center)
+    };
+
+    let face = column![
+        text("Buttons")
+            .size(theme.button_size())
+            .color(theme.text_secondary()),
+        Row::new()
+            .spacing(4)
+            .push(key("LB", GamepadInput::Button(GamepadButton::LeftShoulder)))
+            .push(key(
+                "RB",
+                GamepadInput::Button(GamepadButton::RightShoulder)
+            )),
+        Row::new()
+            .spacing(4)
+            .push(key("B", GamepadInput::Button(GamepadButton::B)))
+            .push(key("A", GamepadInput::Button(GamepadButton::A))),
+        key("≡", GamepadInput::Button(GamepadButton::Menu)),
+    ]
+    .spacing(6)
+    .align_x(Alignment::Center);
+
+    let controls = Row::new()
+        .spacing(32)
+        .align_y(Alignment::End)
+        .push(cross("D-pad", &|direction| {
+            GamepadInput::Button(GamepadButton::DPad(direction))
+        }))
+        .push(cross("Left stick", &|direction| {
+            GamepadInput::Stick(Stick::Left, direction)
+        }))
+        .push(cross("Right stick", &|direction| {
+            GamepadInput::Stick(Stick::Right, direction)
+        }))
+        .push(face);
+
+    let bg_color = theme.sidebar_background();
+
+    column![
+        divider(theme),
+        container(controls)
+            .padding([12, 16])
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(bg_color)),
+                ..Default::default()
+            }),
+    ]
+    .into()
+}
+
+/// Size of a key of the controller panel
+const PAD_KEY_SIZE: f32 = 36.0;
+
+/// A key of the controller panel, held while the mouse button is down over it
+fn pad_key<'a>(
+    label: &'static str,
+    input: GamepadInput,
+    gamepad: &VirtualGamepad,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, GamepadMessage> {
+    let held = gamepad.is_held(input);
+    let status = if held {
+        button::Status::Pressed
+    } else {
+        button::Status::Active
+    };
+    let style = theme.button_style(status, held);
+    let button_font = theme.button_font();
+
+    mouse_area(
+        container(
+            text(label)
+                .size(theme.button_size())
+                .font(button_font.font)
+                .shaping(button_font.shaping),
+        )
+        .width(PAD_KEY_SIZE)
+        .height(PAD_KEY_SIZE)
+        .center_x(PAD_KEY_SIZE)
+        .center_y(PAD_KEY_SIZE)
+        .style(move |_| container::Style {
+            text_color: Some(style.text_color),
+            background: style.background,
+            border: style.border,
+            shadow: style.shadow,
+            ..Default::default()
+        }),
+    )
+    .on_press(GamepadMessage::Press(input))
+    .on_release(GamepadMessage::Release(input))
+    .on_exit(GamepadMessage::Release(input))
+    .interaction(iced::mouse::Interaction::Pointer)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character(key: &str) -> keyboard::Key {
+        keyboard::Key::Character(key.into())
+    }
+
+    fn press(button: GamepadButton) -> GamepadEvent {
+        GamepadEvent::ButtonPressed(button)
+    }
+
+    /// A channel with one running subscription, buffering `capacity` events
+    fn subscribed(capacity: usize) -> (GamepadChannel, mpsc::Receiver<GamepadEvent>) {
+        let channel = GamepadChannel::new();
+        let receiver = channel.subscribe(capacity);
+        (channel, receiver)
+    }
+
+    #[test]
+    fn maps_no_shortcut_keys() {
+        // Layout overlay (L, Shift+L), rulers, measure, focus and perf
+        for key in ["l", "L", "r", "m", "f", "p"] {
+            assert_eq!(key_input(&character(key)), None, "{key}");
+        }
+    }
+
+    #[test]
+    fn maps_sticks() {
+        assert_eq!(
+            key_input(&character("W")),
+            Some(GamepadInput::Stick(Stick::Left, Direction::Up))
+        );
+        for (key, direction) in [
+            ("u", Direction::Up),
+            ("j", Direction::Down),
+            ("h", Direction::Left),
+            ("k", Direction::Right),
+        ] {
+            assert_eq!(
+                key_input(&character(key)),
+                Some(GamepadInput::Stick(Stick::Right, direction))
+            );
+        }
+    }
+
+    #[test]
+    fn channel_delivers_in_order() {
+        let (channel, mut receiver) = subscribed(8);
+        channel.send(press(GamepadButton::A));
+        channel.send(press(GamepadButton::B));
+
+        assert_eq!(receiver.try_recv().unwrap(), press(GamepadButton::A));
+        assert_eq!(receiver.try_recv().unwrap(), press(GamepadButton::B));
+        assert_eq!(channel.dropped(), 0);
+    }
+
+    #[test]
+    fn channel_counts_dropped_events() {
+        // A bounded channel holds its capacity plus one event per sender
+        let (channel, mut receiver) = subscribed(0);
+        channel.send(press(GamepadButton::A));
+        channel.send(press(GamepadButton::B));
+        channel.send(press(GamepadButton::LeftShoulder));
+
+        assert_eq!(channel.dropped(), 2);
+        assert_eq!(receiver.try_recv().unwrap(), press(GamepadButton::A));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn channel_forgets_ended_subscriptions() {
+        let (channel, receiver) = subscribed(8);
+        drop(receiver);
+        channel.send(press(GamepadButton::A));
+
+        assert!(lock(&channel.subscribers).senders.is_empty());
+        assert_eq!(channel.dropped(), 0);
+    }
+
+    #[test]
+    fn channels_are_separate() {
+        let (first, mut first_receiver) = subscribed(8);
+        let (second, mut second_receiver) = subscribed(8);
+        assert_ne!(first.id, second.id);
+
+        second.send(press(GamepadButton::A));
+        assert!(first_receiver.try_recv().is_err());
+        assert_eq!(second_receiver.try_recv().unwrap(), press(GamepadButton::A));
+    }
+
+    #[test]
+    fn sticks_combine_directions() {
+        let mut gamepad = VirtualGamepad::default();
+        gamepad.update(GamepadMessage::Press(GamepadInput::Stick(
+            Stick::Left,
+            Direction::Up,
+        )));
+        let events = gamepad.update(GamepadMessage::Press(GamepadInput::Stick(
+            Stick::Left,
+            Direction::Right,
+        )));
+
+        let [GamepadEvent::StickMoved { stick, position }] = events[..] else {
+            panic!("expected a stick event, got {events:?}");
+        };
+        assert_eq!(stick, Stick::Left);
+        assert!((position.x - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!((position.y + std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+
+        // Turning the gamepad off centers the stick
+        let events = gamepad.update(GamepadMessage::Toggle);
+        assert!(events.is_empty());
+        let events = gamepad.update(GamepadMessage::Toggle);
+        assert_eq!(
+            events,
+            [GamepadEvent::StickMoved {
+                stick: Stick::Left,
+                position: Vector::ZERO,
+            }]
+        );
+    }
+}
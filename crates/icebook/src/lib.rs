@@ -52,24 +52,51 @@
 //!   and rendering functions.
 //! - **ThemeProvider**: Trait that supplies themes. Your registry specifies which provider to use.
 //! - **SidebarTheme**: Minimal theme trait for the sidebar UI. Default implementations provided.
+//! - **ToolbarItem**: Custom toolbar controls (e.g. locale or density) whose values reach
+//!   story views.
 //! - **Storybook**: The main application shell that displays stories.
 //!
 
 mod app;
+mod canvas;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod data_theme;
+mod editor;
+mod fonts;
+mod fullscreen;
+mod gamepad;
+mod perf;
 mod preferences;
 mod routing;
 mod sidebar;
 mod story;
 mod theme;
+pub mod tokens;
+mod toolbar;
+mod transition;
+mod windows;
 
+use app::Startup;
 pub use app::{default_welcome_view, Message, Settings, Storybook};
+pub use canvas::{CanvasBackground, Viewport, DEFAULT_VIEWPORTS};
+pub use data_theme::{DataSidebarTheme, DataThemeError};
+pub use fonts::{FontError, FontFace, FontRegistry, DEFAULT_FONT_SAMPLES};
+pub use gamepad::{Direction, GamepadButton, GamepadEvent, Stick};
 use iced::Font;
-pub use sidebar::{NavItem, SidebarConfig, SidebarMessage, SidebarSection};
-pub use story::{Story, StoryMeta, StoryRegistry};
+pub use sidebar::{NavItem, SidebarConfig, SidebarFocus, SidebarMessage, SidebarSection};
+pub use story::{Story, StoryContext, StoryMeta, StoryRegistry};
 pub use theme::{
-    default_sidebar_theme, Brightness, SidebarFont, SidebarTheme, SimpleDarkSidebar,
-    SimpleLightSidebar, ThemeProvider,
+    default_sidebar_theme, lerp_color, Brightness, SidebarColors, SidebarFont, SidebarTheme,
+    SimpleDarkSidebar, SimpleLightSidebar, ThemeInfo, ThemeMode, ThemeProvider,
+    DEFAULT_BUTTON_SIZE, DEFAULT_NAV_SIZE, DEFAULT_SECTION_SIZE, DEFAULT_SIDEBAR_WIDTH,
+    DEFAULT_TITLE_SIZE,
 };
+pub use tokens::{
+    DesignToken, SidebarTokenPaths, Token, TokenData, TokenError, TokenTable, TokenValue,
+};
+pub use toolbar::{ToolbarItem, ToolbarKind, ToolbarValue, ToolbarValues};
+pub use transition::TransitionProgress;
 
 /// Built-in fallback font (Fira Sans Regular)
 ///
@@ -89,13 +116,21 @@ pub const FALLBACK_FONT_NAME: &str = "Fira Sans";
 /// Prelude for convenient imports
 pub mod prelude {
     pub use crate::app::{default_welcome_view, Message, Settings, Storybook};
+    pub use crate::canvas::{CanvasBackground, Viewport};
+    pub use crate::data_theme::DataSidebarTheme;
+    pub use crate::fonts::{FontFace, FontRegistry};
+    pub use crate::gamepad::{Direction, GamepadButton, GamepadEvent, Stick};
     pub use crate::run;
-    pub use crate::sidebar::{NavItem, SidebarConfig, SidebarMessage, SidebarSection};
-    pub use crate::story::{Story, StoryMeta, StoryRegistry};
+    pub use crate::sidebar::{
+        NavItem, SidebarConfig, SidebarFocus, SidebarMessage, SidebarSection,
+    };
+    pub use crate::story::{Story, StoryContext, StoryMeta, StoryRegistry};
     pub use crate::theme::{
-        default_sidebar_theme, Brightness, SidebarFont, SidebarTheme, SimpleDarkSidebar,
-        SimpleLightSidebar, ThemeProvider,
+        default_sidebar_theme, lerp_color, Brightness, SidebarColors, SidebarFont, SidebarTheme,
+        SimpleDarkSidebar, SimpleLightSidebar, ThemeInfo, ThemeMode, ThemeProvider,
     };
+    pub use crate::tokens::{DesignToken, SidebarTokenPaths, TokenTable, TokenValue};
+    pub use crate::toolbar::{ToolbarItem, ToolbarValue, ToolbarValues};
     pub use crate::{FALLBACK_FONT, FALLBACK_FONT_NAME};
 }
 
@@ -145,10 +180,38 @@ where
     S::Message: std::fmt::Debug + Clone + Send,
 {
     #[cfg(target_arch = "wasm32")]
-    init_wasm();
+    {
+        init_wasm();
+        run_application::<S>(settings)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    run_daemon::<S>(settings)
+}
+
+/// Parse the consumer's fonts once, for loading into iced and for the fonts
+/// page, warning about those that fail to parse
+fn load_fonts(fonts: &[&'static [u8]]) -> FontRegistry {
+    let registry = FontRegistry::new(fonts);
+    for error in registry.errors() {
+        tracing::warn!("Skipping invalid {}", error);
+    }
+
+    registry
+}
+
+/// Run as a single-window application (WASM has no multi-window support)
+#[cfg(target_arch = "wasm32")]
+fn run_application<S>(settings: Settings) -> iced::Result
+where
+    S: StoryRegistry + 'static,
+    S::Message: std::fmt::Debug + Clone + Send,
+{
+    let fonts = load_fonts(settings.fonts);
+    let files = fonts.files().to_vec();
 
     let mut app = iced::application(
-        Storybook::<S>::default,
+        move || Storybook::<S>::with_fonts(settings, fonts.clone(), Startup::detect()),
         Storybook::<S>::update,
         Storybook::<S>::view,
     )
@@ -159,9 +222,52 @@ where
     .font(FALLBACK_FONT)
     .default_font(Font::with_name(FALLBACK_FONT_NAME));
 
-    // Load all custom fonts from consumer
-    for font_bytes in settings.fonts {
-        app = app.font(*font_bytes);
+    // Load the consumer's fonts that parse
+    for font in files {
+        app = app.font(font);
+    }
+
+    app.run()
+}
+
+/// Run as a multi-window daemon, so stories can be popped out into their own windows
+#[cfg(not(target_arch = "wasm32"))]
+fn run_daemon<S>(settings: Settings) -> iced::Result
+where
+    S: StoryRegistry + 'static,
+    S::Message: std::fmt::Debug + Clone + Send,
+{
+    // `--list` prints the registry instead of launching
+    let launch = cli::launch_options();
+    if launch.list {
+        cli::print_stories(&app::story_list::<S>());
+        return Ok(());
+    }
+
+    let settings = Settings {
+        window_size: launch.window_size.unwrap_or(settings.window_size),
+        ..settings
+    };
+
+    let fonts = load_fonts(settings.fonts);
+    let files = fonts.files().to_vec();
+
+    let mut app = iced::daemon(
+        move || {
+            Storybook::<S>::with_main_window_and_fonts(settings, fonts.clone(), Startup::detect())
+        },
+        Storybook::<S>::update,
+        Storybook::<S>::view_window,
+    )
+    .title(Storybook::<S>::title_window)
+    .theme(Storybook::<S>::theme_window)
+    .subscription(Storybook::<S>::subscription)
+    .font(FALLBACK_FONT)
+    .default_font(Font::with_name(FALLBACK_FONT_NAME));
+
+    // Load the consumer's fonts that parse
+    for font in files {
+        app = app.font(font);
     }
 
     app.run()
@@ -0,0 +1,395 @@
+//! Performance HUD for the selected story
+//!
+//! While the HUD is on, the storybook redraws every frame and records, per frame,
+//! the time since the previous frame, the time spent building the story's view,
+//! the time spent in the story's `update` and the number of story messages. The
+//! HUD sums up the last `HISTORY` frames and graphs them, so a stuttering story
+//! shows whether its frames go into view construction, updates or elsewhere
+//! (layout and drawing).
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+
+use iced::time::{Duration, Instant};
+use iced::widget::{column, container, row, text, Row, Space};
+use iced::{Alignment, Border, Color, Element, Length};
+
+use crate::theme::SidebarTheme;
+
+/// Number of frames kept for the summary and the graph
+const HISTORY: usize = 120;
+
+/// Frame time shown at the full height of the graph (three 60 Hz frames)
+const GRAPH_MAX: Duration = Duration::from_millis(50);
+
+/// Height of the graph
+const GRAPH_HEIGHT: f32 = 40.0;
+
+/// Width of a frame's bar in the graph
+const BAR_WIDTH: f32 = 2.0;
+
+/// What happened during one frame
+#[derive(Debug, Clone, Copy, Default)]
+struct FrameSample {
+    /// Time since the previous frame
+    interval: Duration,
+    /// Time spent building the story's view
+    view: Duration,
+    /// Views built
+    views: u32,
+    /// Longest view build
+    max_view: Duration,
+    /// Time spent in the story's `update`
+    update: Duration,
+    /// Story messages handled
+    messages: u32,
+    /// Longest `update` call
+    max_update: Duration,
+}
+
+/// Timings of the selected story, recorded while the HUD is on
+#[derive(Debug, Default)]
+pub struct PerfMonitor {
+    enabled: bool,
+    /// Time of the previous frame
+    last_frame: Option<Instant>,
+    /// Finished frames, oldest first
+    history: VecDeque<FrameSample>,
+    /// The frame in progress (views are timed in `view`, hence the cell)
+    current: Cell<FrameSample>,
+}
+
+impl PerfMonitor {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Show or hide the HUD, starting from empty timings
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.reset();
+    }
+
+    /// Forget the recorded timings (e.g. when another story is selected)
+    pub fn reset(&mut self) {
+        self.last_frame = None;
+        self.history.clear();
+        self.current.take();
+    }
+
+    /// Build the story's view, timing it while the HUD is on
+    pub fn time_view<T>(&self, view: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return view();
+        }
+
+        let start = Instant::now();
+        let element = view();
+        self.record_view(start.elapsed());
+
+        element
+    }
+
+    fn record_view(&self, elapsed: Duration) {
+        let mut current = self.current.get();
+        current.view += elapsed;
+        current.views += 1;
+        current.max_view = current.max_view.max(elapsed);
+        self.current.set(current);
+    }
+
+    /// Run the story's `update`, timing it while the HUD is on
+    pub fn time_update<T>(&mut self, update: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return update();
+        }
+
+        let start = Instant::now();
+        let output = update();
+        self.record_update(start.elapsed());
+
+        output
+    }
+
+    fn record_update(&mut self, elapsed: Duration) {
+        let current = self.current.get_mut();
+        current.update += elapsed;
+        current.messages += 1;
+        current.max_update = current.max_update.max(elapsed);
+    }
+
+    /// Close the frame in progress
+    pub fn frame(&mut self, now: Instant) {
+        let Some(last_frame) = self.last_frame.replace(now) else {
+            self.current.take();
+            return;
+        };
+
+        let mut sample = self.current.take();
+        sample.interval = now.saturating_duration_since(last_frame);
+
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(sample);
+    }
+
+    /// Totals over the recorded frames (`None` before the first frame)
+    fn summary(&self) -> Option<Summary> {
+        let elapsed: Duration = self.history.iter().map(|sample| sample.interval).sum();
+        if elapsed.is_zero() {
+            return None;
+        }
+
+        let total = self
+            .history
+            .iter()
+            .fold(FrameSample::default(), |total, sample| FrameSample {
+                interval: total.interval + sample.interval,
+                view: total.view + sample.view,
+                views: total.views + sample.views,
+                max_view: total.max_view.max(sample.max_view),
+                update: total.update + sample.update,
+                messages: total.messages + sample.messages,
+                max_update: total.max_update.max(sample.max_update),
+            });
+        let seconds = elapsed.as_secs_f32();
+
+        Some(Summary {
+            fps: self.history.len() as f32 / seconds,
+            view: average(total.view, total.views),
+            max_view: total.max_view,
+            update: average(total.update, total.messages),
+            max_update: total.max_update,
+            messages_per_second: total.messages as f32 / seconds,
+        })
+    }
+}
+
+/// Per-second rates and per-call times over the recorded frames
+struct Summary {
+    fps: f32,
+    /// Average time per view build
+    view: Duration,
+    max_view: Duration,
+    /// Average time per message
+    update: Duration,
+    max_update: Duration,
+    messages_per_second: f32,
+}
+
+fn average(total: Duration, count: u32) -> Duration {
+    if count == 0 {
+        Duration::ZERO
+    } else {
+        total / count
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Render the HUD shown in the corner of the canvas
+pub fn perf_hud<'a, Message: 'a>(
+    monitor: &PerfMonitor,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, Message> {
+    let size = theme.button_size();
+    let primary = theme.text_primary();
+    let secondary = theme.text_secondary();
+    let button_font = theme.button_font();
+
+    let line = |label: &'static str, value: String| {
+        row![
+            text(label).size(size).color(secondary).width(Length::Fill),
+            text(value)
+                .size(size)
+                .color(primary)
+                .font(button_font.font)
+                .shaping(button_font.shaping),
+        ]
+    };
+
+    let stats = match monitor.summary() {
+        Some(summary) => column![
+            line("Frames", format!("{:.0} fps", summary.fps)),
+            line(
+                "View",
+                format!(
+                    "{} (max {})",
+                    millis(summary.view),
+                    millis(summary.max_view)
+                ),
+            ),
+            line(
+                "Update / msg",
+                format!(
+                    "{} (max {})",
+                    millis(summary.update),
+                    millis(summary.max_update)
+                ),
+            ),
+            line(
+                "Messages",
+                format!("{:.0} / s", summary.messages_per_second),
+            ),
+        ],
+        None => column![text("Measuring...").size(size).color(secondary)],
+    };
+
+    let bg_color = theme.sidebar_background();
+    let border_color = theme.divider_color();
+
+    container(
+        column![
+            stats.spacing(4),
+            graph(monitor, primary, secondary),
+            text("Frame time, with view + update highlighted")
+                .size(size - 2.0)
+                .color(secondary),
+        ]
+        .spacing(8),
+    )
+    .padding(10)
+    .width(Length::Fixed(HISTORY as f32 * BAR_WIDTH + 20.0))
+    .style(move |_| container::Style {
+        background: Some(iced::Background::Color(bg_color)),
+        border: Border {
+            color: border_color,
+            width: 1.0,
+            radius: 6.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+/// Bars of the recorded frames (newest on the right), each as tall as the frame
+/// time, with the time spent in the story's view and update at the bottom
+fn graph<'a, Message: 'a>(
+    monitor: &PerfMonitor,
+    work_color: Color,
+    frame_color: Color,
+) -> Element<'a, Message> {
+    let height = |duration: Duration| {
+        (duration.as_secs_f32() / GRAPH_MAX.as_secs_f32()).min(1.0) * GRAPH_HEIGHT
+    };
+    let block = |height: f32, color: Color| {
+        container(Space::new())
+            .width(BAR_WIDTH)
+            .height(height)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(color)),
+                ..Default::default()
+            })
+    };
+
+    let bars = monitor.history.iter().map(|sample| {
+        let frame = height(sample.interval);
+        let work = height(sample.view + sample.update).min(frame);
+
+        column![block(frame - work, frame_color), block(work, work_color)].into()
+    });
+
+    container(Row::with_children(bars).align_y(Alignment::End))
+        .width(Length::Fill)
+        .height(GRAPH_HEIGHT)
+        .align_right(Length::Fill)
+        .align_bottom(GRAPH_HEIGHT)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// A HUD that saw its first frame at `start`
+    fn started(start: Instant) -> PerfMonitor {
+        let mut monitor = PerfMonitor::default();
+        monitor.toggle();
+        monitor.frame(start);
+        monitor
+    }
+
+    #[test]
+    fn skips_the_first_frame() {
+        let mut monitor = PerfMonitor::default();
+        monitor.toggle();
+        monitor.record_view(ms(5));
+        monitor.frame(Instant::now());
+
+        assert!(monitor.history.is_empty());
+        assert!(monitor.summary().is_none());
+        // Work before the first frame is not counted in the next one
+        assert_eq!(monitor.current.get().views, 0);
+    }
+
+    #[test]
+    fn keeps_the_last_frames() {
+        let start = Instant::now();
+        let mut monitor = started(start);
+
+        for frame in 1..=HISTORY as u64 + 10 {
+            monitor.frame(start + ms(10 * frame));
+        }
+        assert_eq!(monitor.history.len(), HISTORY);
+    }
+
+    #[test]
+    fn summarizes_frames() {
+        let start = Instant::now();
+        let mut monitor = started(start);
+
+        monitor.record_view(ms(2));
+        monitor.record_view(ms(4));
+        monitor.record_update(ms(1));
+        monitor.frame(start + ms(20));
+        monitor.record_view(ms(6));
+        monitor.record_update(ms(3));
+        monitor.record_update(ms(2));
+        monitor.frame(start + ms(40));
+
+        let summary = monitor.summary().unwrap();
+        assert!((summary.fps - 50.0).abs() < 1e-3);
+        assert_eq!(summary.view, ms(4));
+        assert_eq!(summary.max_view, ms(6));
+        assert_eq!(summary.update, ms(2));
+        assert_eq!(summary.max_update, ms(3));
+        assert!((summary.messages_per_second - 75.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn averages_nothing_as_zero() {
+        let start = Instant::now();
+        let mut monitor = started(start);
+        monitor.frame(start + ms(16));
+
+        let summary = monitor.summary().unwrap();
+        assert_eq!(summary.view, Duration::ZERO);
+        assert_eq!(summary.update, Duration::ZERO);
+        assert_eq!(summary.messages_per_second, 0.0);
+    }
+
+    #[test]
+    fn records_only_while_enabled() {
+        let mut monitor = PerfMonitor::default();
+        monitor.time_view(|| ());
+        monitor.time_update(|| ());
+        assert_eq!(monitor.current.get().views, 0);
+        assert_eq!(monitor.current.get().messages, 0);
+
+        monitor.toggle();
+        monitor.time_view(|| ());
+        monitor.time_update(|| ());
+        assert_eq!(monitor.current.get().views, 1);
+        assert_eq!(monitor.current.get().messages, 1);
+
+        monitor.reset();
+        assert_eq!(monitor.current.get().views, 0);
+    }
+}
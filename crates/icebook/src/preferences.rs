@@ -1,37 +1,48 @@
 //! User preferences with localStorage persistence (WASM) and system theme detection
 
-use crate::theme::Brightness;
+use iced::{Subscription, Task};
+
+use crate::theme::{Brightness, ThemeMode};
 
 #[cfg(target_arch = "wasm32")]
 const THEME_KEY: &str = "icebook_theme";
 
+#[cfg(target_arch = "wasm32")]
+const DARK_SCHEME_QUERY: &str = "(prefers-color-scheme: dark)";
+
+#[cfg(target_arch = "wasm32")]
+const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+
 /// User preferences for the storybook
 #[derive(Debug, Clone)]
 pub struct Preferences {
-    brightness: Brightness,
+    theme_mode: ThemeMode,
 }
 
 impl Preferences {
-    /// Load preferences (from localStorage on WASM, defaults on native)
+    /// Load preferences (from localStorage on WASM, launch options on native)
     pub fn load() -> Self {
-        Self {
-            brightness: get_initial_brightness(),
-        }
+        Self::new(load_theme_mode().unwrap_or_default())
     }
 
-    /// Get the current brightness preference
-    pub fn brightness(&self) -> Brightness {
-        self.brightness
+    /// Preferences starting from the given theme mode
+    pub fn new(theme_mode: ThemeMode) -> Self {
+        Self { theme_mode }
     }
 
-    /// Set the brightness preference
-    pub fn set_brightness(&mut self, brightness: Brightness) {
-        self.brightness = brightness;
+    /// Get the current theme mode preference
+    pub fn theme_mode(&self) -> &ThemeMode {
+        &self.theme_mode
+    }
+
+    /// Set the theme mode preference
+    pub fn set_theme_mode(&mut self, theme_mode: ThemeMode) {
+        self.theme_mode = theme_mode;
     }
 
     /// Save preferences (to localStorage on WASM, no-op on native)
     pub fn save(&self) {
-        save_brightness(self.brightness);
+        save_theme_mode(&self.theme_mode);
     }
 }
 
@@ -41,47 +52,130 @@ impl Default for Preferences {
     }
 }
 
-/// Get the initial brightness based on: saved preference > system preference > default (dark)
-pub fn get_initial_brightness() -> Brightness {
-    // First check if user has a saved preference
-    if let Some(saved) = load_brightness() {
-        return saved;
-    }
+/// Get the system/browser color scheme preference
+///
+/// Falls back to `Brightness::default()` when the scheme cannot be read synchronously.
+/// On native, the real system theme arrives through `detect_system_brightness`.
+#[cfg(target_arch = "wasm32")]
+pub fn system_brightness() -> Brightness {
+    dark_scheme_query()
+        .map(|query| brightness_from_dark(query.matches()))
+        .unwrap_or_default()
+}
 
-    // Fall back to system preference
-    get_system_brightness()
+#[cfg(not(target_arch = "wasm32"))]
+pub fn system_brightness() -> Brightness {
+    Brightness::default()
 }
 
-/// Get the system/browser color scheme preference
+/// Query the system theme asynchronously (native only; WASM reads it synchronously)
 #[cfg(target_arch = "wasm32")]
-fn get_system_brightness() -> Brightness {
-    let window = match web_sys::window() {
-        Some(w) => w,
-        None => return Brightness::Dark,
-    };
+pub fn detect_system_brightness() -> Task<Brightness> {
+    Task::none()
+}
 
-    let result = window.match_media("(prefers-color-scheme: dark)");
+#[cfg(not(target_arch = "wasm32"))]
+pub fn detect_system_brightness() -> Task<Brightness> {
+    iced::system::theme()
+        .map(brightness_from_mode)
+        .and_then(Task::done)
+}
 
-    match result {
-        Ok(Some(media_query)) => {
-            if media_query.matches() {
-                Brightness::Dark
-            } else {
-                Brightness::Light
-            }
+/// Subscribe to system color scheme changes
+///
+/// On WASM this registers a `change` listener on the `prefers-color-scheme`
+/// media query, which is removed again when the subscription is dropped.
+#[cfg(target_arch = "wasm32")]
+pub fn system_brightness_changes() -> Subscription<Brightness> {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    /// Keeps the listener alive and unregisters it on drop
+    struct Listener {
+        query: web_sys::MediaQueryList,
+        callback: Closure<dyn FnMut(web_sys::MediaQueryListEvent)>,
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = self.query.remove_event_listener_with_callback(
+                "change",
+                self.callback.as_ref().unchecked_ref(),
+            );
         }
-        _ => Brightness::Dark,
     }
+
+    Subscription::run(|| {
+        iced::stream::channel(4, async |mut output| {
+            let Some(query) = dark_scheme_query() else {
+                return;
+            };
+
+            let callback = Closure::<dyn FnMut(web_sys::MediaQueryListEvent)>::new(
+                move |event: web_sys::MediaQueryListEvent| {
+                    let _ = output.try_send(brightness_from_dark(event.matches()));
+                },
+            );
+
+            if query
+                .add_event_listener_with_callback("change", callback.as_ref().unchecked_ref())
+                .is_err()
+            {
+                return;
+            }
+
+            let _listener = Listener { query, callback };
+            iced::futures::future::pending::<()>().await;
+        })
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn system_brightness_changes() -> Subscription<Brightness> {
+    iced::system::theme_changes().filter_map(brightness_from_mode)
+}
+
+/// Whether the browser asks for reduced motion (`prefers-reduced-motion: reduce`)
+///
+/// Read on every theme change, so changing the setting applies without a reload.
+#[cfg(target_arch = "wasm32")]
+pub fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media(REDUCED_MOTION_QUERY).ok().flatten())
+        .is_some_and(|query| query.matches())
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn get_system_brightness() -> Brightness {
-    Brightness::Dark
+pub fn prefers_reduced_motion() -> bool {
+    false
+}
+
+#[cfg(target_arch = "wasm32")]
+fn dark_scheme_query() -> Option<web_sys::MediaQueryList> {
+    web_sys::window()?.match_media(DARK_SCHEME_QUERY).ok()?
 }
 
-/// Save brightness preference
 #[cfg(target_arch = "wasm32")]
-pub fn save_brightness(brightness: Brightness) {
+fn brightness_from_dark(is_dark: bool) -> Brightness {
+    if is_dark {
+        Brightness::Dark
+    } else {
+        Brightness::Light
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn brightness_from_mode(mode: iced::theme::Mode) -> Option<Brightness> {
+    match mode {
+        iced::theme::Mode::Dark => Some(Brightness::Dark),
+        iced::theme::Mode::Light => Some(Brightness::Light),
+        iced::theme::Mode::None => None,
+    }
+}
+
+/// Save theme mode preference
+#[cfg(target_arch = "wasm32")]
+pub fn save_theme_mode(theme_mode: &ThemeMode) {
     let window = match web_sys::window() {
         Some(w) => w,
         None => return,
@@ -92,34 +186,27 @@ pub fn save_brightness(brightness: Brightness) {
         _ => return,
     };
 
-    let value = match brightness {
-        Brightness::Dark => "dark",
-        Brightness::Light => "light",
-    };
-
-    let _ = storage.set_item(THEME_KEY, value);
+    let _ = storage.set_item(THEME_KEY, theme_mode.id());
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub fn save_brightness(_brightness: Brightness) {
+pub fn save_theme_mode(_theme_mode: &ThemeMode) {
     // No persistence on native
 }
 
-/// Load brightness preference
+/// Load theme mode preference
 #[cfg(target_arch = "wasm32")]
-pub fn load_brightness() -> Option<Brightness> {
+pub fn load_theme_mode() -> Option<ThemeMode> {
     let window = web_sys::window()?;
     let storage = window.local_storage().ok()??;
     let value = storage.get_item(THEME_KEY).ok()??;
 
-    match value.as_str() {
-        "dark" => Some(Brightness::Dark),
-        "light" => Some(Brightness::Light),
-        _ => None,
-    }
+    // Values are "system" or a theme id ("light"/"dark" before named themes)
+    (!value.is_empty()).then(|| ThemeMode::from_id(&value))
 }
 
+/// Load theme mode preference (native: from `--theme` or `ICEBOOK_THEME`)
 #[cfg(not(target_arch = "wasm32"))]
-pub fn load_brightness() -> Option<Brightness> {
-    None
+pub fn load_theme_mode() -> Option<ThemeMode> {
+    crate::cli::launch_options().theme_mode.clone()
 }
@@ -1,5 +1,27 @@
 //! URL hash-based routing for persisting selected story across page refreshes
 
+use crate::story::StoryMeta;
+use crate::theme::ThemeMode;
+
+/// Maximum number of "did you mean" suggestions on the not-found page
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Shortest text that counts as a match when found inside a longer route or
+/// candidate (so short ids like "ui" don't match every route)
+const MIN_SUBSTRING_MATCH: usize = 3;
+
+/// URL query parameter that enables embed mode
+#[cfg(target_arch = "wasm32")]
+const EMBED_PARAM: &str = "embed";
+
+/// URL query parameter that stores the selected theme id
+#[cfg(target_arch = "wasm32")]
+const THEME_PARAM: &str = "theme";
+
+/// URL query parameter that stores the values of the consumer's toolbar items
+#[cfg(target_arch = "wasm32")]
+const TOOLBAR_PARAM: &str = "toolbar";
+
 /// Get the initial route from URL hash (WASM only)
 #[cfg(target_arch = "wasm32")]
 pub fn get_initial_route() -> Option<String> {
@@ -7,22 +29,175 @@ pub fn get_initial_route() -> Option<String> {
     let location = window.location();
     let hash = location.hash().ok()?;
 
-    // Strip leading # and / if present
+    // Strip leading # and / if present (case is preserved, ids are matched exactly first)
     let route = hash.trim_start_matches('#').trim_start_matches('/');
 
     if route.is_empty() {
         None
     } else {
-        Some(route.to_lowercase())
+        Some(route.to_string())
     }
 }
 
-/// Get initial route (native: always None)
+/// Get initial route (native: from `--story` or `ICEBOOK_STORY`)
 #[cfg(not(target_arch = "wasm32"))]
 pub fn get_initial_route() -> Option<String> {
+    crate::cli::launch_options().story.clone()
+}
+
+/// Check the URL query for the embed flag, e.g. `?embed=1#/buttons` (WASM only)
+#[cfg(target_arch = "wasm32")]
+pub fn embed_requested() -> bool {
+    query_params()
+        .and_then(|params| params.get(EMBED_PARAM))
+        .is_some_and(|value| matches!(value.as_str(), "" | "1" | "true"))
+}
+
+/// Check for embed flag (native: always false, use `Settings::embed`)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn embed_requested() -> bool {
+    false
+}
+
+/// Get the theme id from the URL query, e.g. `?theme=high-contrast` (WASM only)
+#[cfg(target_arch = "wasm32")]
+pub fn get_url_theme() -> Option<String> {
+    query_params()?
+        .get(THEME_PARAM)
+        .filter(|theme| !theme.is_empty())
+}
+
+/// Get theme from URL (native: always None, use `--theme`)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_url_theme() -> Option<String> {
     None
 }
 
+/// Store the selected theme id in the URL query, or remove it with `None` (WASM only)
+#[cfg(target_arch = "wasm32")]
+pub fn set_url_theme(theme_id: Option<&str>) {
+    set_query_param(THEME_PARAM, theme_id);
+}
+
+/// Update URL theme (native: no-op)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_url_theme(_theme_id: Option<&str>) {
+    // No URL routing on native
+}
+
+/// Get the toolbar values from the URL query, e.g. `?toolbar=locale:fr,density:compact`
+/// (WASM only)
+#[cfg(target_arch = "wasm32")]
+pub fn get_url_toolbar() -> Option<String> {
+    query_params()?
+        .get(TOOLBAR_PARAM)
+        .filter(|values| !values.is_empty())
+}
+
+/// Get toolbar values (native: from `--toolbar` or `ICEBOOK_TOOLBAR`)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_url_toolbar() -> Option<String> {
+    crate::cli::launch_options().toolbar.clone()
+}
+
+/// Store the toolbar values in the URL query, or remove them with `None` (WASM only)
+#[cfg(target_arch = "wasm32")]
+pub fn set_url_toolbar(values: Option<&str>) {
+    set_query_param(TOOLBAR_PARAM, values);
+}
+
+/// Update URL toolbar values (native: no-op)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_url_toolbar(_values: Option<&str>) {
+    // No URL routing on native
+}
+
+/// Set or remove a URL query parameter, keeping the hash
+#[cfg(target_arch = "wasm32")]
+fn set_query_param(name: &str, value: Option<&str>) {
+    let window = match web_sys::window() {
+        Some(w) => w,
+        None => return,
+    };
+    let params = match query_params() {
+        Some(params) => params,
+        None => return,
+    };
+
+    match value {
+        Some(value) => params.set(name, value),
+        None => params.delete(name),
+    }
+
+    let location = window.location();
+    let path = location.pathname().unwrap_or_default();
+    let hash = location.hash().unwrap_or_default();
+    let query = String::from(params.to_string());
+    let url = if query.is_empty() {
+        format!("{}{}", path, hash)
+    } else {
+        format!("{}?{}{}", path, query, hash)
+    };
+
+    if let Ok(history) = window.history() {
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+    }
+}
+
+/// Label of the toolbar button copying `share_link`
+#[cfg(target_arch = "wasm32")]
+pub const SHARE_LABEL: &str = "Copy link";
+
+/// Label of the toolbar button copying `share_link` (native: launch arguments)
+#[cfg(not(target_arch = "wasm32"))]
+pub const SHARE_LABEL: &str = "Copy launch args";
+
+/// Link to the current story, theme and toolbar values (WASM: the page URL,
+/// which is kept in sync)
+#[cfg(target_arch = "wasm32")]
+pub fn share_link(_story_id: &str, _theme_mode: &ThemeMode, _toolbar: Option<&str>) -> String {
+    web_sys::window()
+        .and_then(|window| window.location().href().ok())
+        .unwrap_or_default()
+}
+
+/// Link to the current story, theme and toolbar values (native: the launch
+/// arguments that reopen them, quoted for a POSIX shell)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn share_link(story_id: &str, theme_mode: &ThemeMode, toolbar: Option<&str>) -> String {
+    let mut args = Vec::new();
+    if !story_id.is_empty() {
+        args.push(format!("--story {}", shell_quote(story_id)));
+    }
+    args.push(format!("--theme {}", shell_quote(theme_mode.id())));
+    if let Some(toolbar) = toolbar {
+        args.push(format!("--toolbar {}", shell_quote(toolbar)));
+    }
+    args.join(" ")
+}
+
+/// Quote an argument for a POSIX shell, unless it only has safe characters
+#[cfg(not(target_arch = "wasm32"))]
+fn shell_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.,:/=@+".contains(c));
+
+    if safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Parse the current URL query
+#[cfg(target_arch = "wasm32")]
+fn query_params() -> Option<web_sys::UrlSearchParams> {
+    let search = web_sys::window()?.location().search().ok()?;
+    web_sys::UrlSearchParams::new_with_str(&search).ok()
+}
+
 /// Update the URL hash when story changes (WASM only)
 #[cfg(target_arch = "wasm32")]
 pub fn set_url_hash(story_id: &str) {
@@ -48,3 +223,217 @@ pub fn set_url_hash(story_id: &str) {
 pub fn set_url_hash(_story_id: &str) {
     // No URL routing on native
 }
+
+/// Resolve a route to a story id
+///
+/// Matches story ids exactly, then aliases, then ids and aliases case-insensitively
+/// (links created before ids were case-sensitive were always lowercased).
+pub fn resolve_route(route: &str, stories: &[StoryMeta]) -> Option<&'static str> {
+    stories
+        .iter()
+        .find(|story| story.id == route)
+        .or_else(|| stories.iter().find(|story| story.aliases.contains(&route)))
+        .or_else(|| {
+            stories.iter().find(|story| {
+                story.id.eq_ignore_ascii_case(route)
+                    || story
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(route))
+            })
+        })
+        .map(|story| story.id)
+}
+
+/// Suggest stories whose id, title or aliases are close to an unknown route
+pub fn suggest_stories<'a>(route: &str, stories: &'a [StoryMeta]) -> Vec<&'a StoryMeta> {
+    let route = route.to_lowercase();
+
+    let mut scored: Vec<(usize, &StoryMeta)> = stories
+        .iter()
+        .filter_map(|story| {
+            std::iter::once(story.id)
+                .chain(std::iter::once(story.title))
+                .chain(story.aliases.iter().copied())
+                .filter_map(|candidate| match_score(&route, &candidate.to_lowercase()))
+                .min()
+                .map(|score| (score, story))
+        })
+        .collect();
+
+    scored.sort_by_key(|(score, _)| *score);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, story)| story)
+        .collect()
+}
+
+/// Score how closely a candidate matches a route (lower is better, `None` if unrelated)
+fn match_score(route: &str, candidate: &str) -> Option<usize> {
+    let contains =
+        |text: &str, part: &str| part.chars().count() >= MIN_SUBSTRING_MATCH && text.contains(part);
+    if contains(candidate, route) || contains(route, candidate) {
+        return Some(0);
+    }
+
+    let distance = edit_distance(route, candidate);
+    let threshold = (route.chars().count().max(candidate.chars().count()) / 3).max(2);
+
+    (distance <= threshold).then_some(distance)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stories() -> Vec<StoryMeta> {
+        vec![
+            StoryMeta::new("buttons", "Buttons", "Actions").aliases(&["button", "Btn"]),
+            StoryMeta::new("inputs", "Inputs", "Forms").aliases(&["text-fields"]),
+            StoryMeta::new("Menu", "Menu", "Navigation"),
+            StoryMeta::new("toggles", "Toggles", "Forms"),
+        ]
+    }
+
+    #[test]
+    fn resolves_exact_ids() {
+        assert_eq!(resolve_route("buttons", &stories()), Some("buttons"));
+        assert_eq!(resolve_route("Menu", &stories()), Some("Menu"));
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        assert_eq!(resolve_route("button", &stories()), Some("buttons"));
+        assert_eq!(resolve_route("text-fields", &stories()), Some("inputs"));
+    }
+
+    #[test]
+    fn resolves_case_insensitively() {
+        assert_eq!(resolve_route("BUTTONS", &stories()), Some("buttons"));
+        assert_eq!(resolve_route("menu", &stories()), Some("Menu"));
+        assert_eq!(resolve_route("btn", &stories()), Some("buttons"));
+    }
+
+    #[test]
+    fn exact_match_wins_over_alias() {
+        let stories = vec![
+            StoryMeta::new("old", "New", "A").aliases(&["legacy"]),
+            StoryMeta::new("legacy", "Legacy", "A"),
+        ];
+        assert_eq!(resolve_route("legacy", &stories), Some("legacy"));
+    }
+
+    #[test]
+    fn unknown_routes_resolve_to_none() {
+        assert_eq!(resolve_route("sliders", &stories()), None);
+        assert_eq!(resolve_route("", &stories()), None);
+    }
+
+    #[test]
+    fn suggests_close_matches_first() {
+        let stories = stories();
+        let ids: Vec<_> = suggest_stories("buttns", &stories)
+            .iter()
+            .map(|story| story.id)
+            .collect();
+        assert_eq!(ids.first(), Some(&"buttons"));
+    }
+
+    #[test]
+    fn suggests_substring_matches() {
+        let stories = stories();
+        let ids: Vec<_> = suggest_stories("text", &stories)
+            .iter()
+            .map(|story| story.id)
+            .collect();
+        assert_eq!(ids, ["inputs"]);
+    }
+
+    #[test]
+    fn short_ids_and_routes_are_no_substring_matches() {
+        let stories = vec![
+            StoryMeta::new("ui", "UI", "Overview"),
+            StoryMeta::new("guide-lines", "Guide lines", "Overview"),
+        ];
+        let ids = |route| -> Vec<&str> {
+            suggest_stories(route, &stories)
+                .iter()
+                .map(|story| story.id)
+                .collect()
+        };
+
+        assert_eq!(ids("guide-lines-old"), ["guide-lines"]);
+        assert_eq!(ids("guide"), ["guide-lines"]);
+        assert_eq!(match_score("guide-lines-old", "ui"), None);
+        assert_eq!(match_score("e", "guide-lines"), None);
+        assert_eq!(match_score("gui", "guide-lines"), Some(0));
+    }
+
+    #[test]
+    fn suggests_nothing_for_unrelated_routes() {
+        assert!(suggest_stories("zzzzzzzzzz", &stories()).is_empty());
+    }
+
+    #[test]
+    fn caps_suggestions() {
+        let stories: Vec<StoryMeta> = ["card-a", "card-b", "card-c", "card-d", "card-e", "card-f"]
+            .into_iter()
+            .map(|id| StoryMeta::new(id, id, "Cards"))
+            .collect();
+        assert_eq!(suggest_stories("card", &stories).len(), MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("abc", "abd"), 1);
+        assert_eq!(edit_distance("abc", "ab"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn share_link_lists_launch_args() {
+        let theme = ThemeMode::Named("dark".to_string());
+
+        assert_eq!(
+            share_link("buttons", &theme, None),
+            "--story buttons --theme dark"
+        );
+        assert_eq!(share_link("", &ThemeMode::System, None), "--theme system");
+        assert_eq!(
+            share_link("buttons", &theme, Some("locale:de,density:compact")),
+            "--story buttons --theme dark --toolbar locale:de,density:compact"
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn share_link_quotes_args() {
+        let theme = ThemeMode::Named("high contrast".to_string());
+
+        assert_eq!(
+            share_link("it's $HOME", &theme, Some("label:a b")),
+            r"--story 'it'\''s $HOME' --theme 'high contrast' --toolbar 'label:a b'"
+        );
+    }
+}
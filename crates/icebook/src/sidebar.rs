@@ -1,9 +1,9 @@
 //! Generic sidebar navigation component
 
-use iced::widget::{button, column, container, scrollable, text, text_input, Column, Space};
+use iced::widget::{button, column, container, rule, scrollable, text, text_input, Column, Space};
 use iced::{Color, Element, Length};
 
-use crate::theme::SidebarTheme;
+use crate::theme::{SidebarTheme, ThemeInfo};
 
 /// A navigation item in the sidebar
 #[derive(Debug, Clone)]
@@ -24,14 +24,101 @@ pub struct SidebarSection {
 pub struct SidebarConfig {
     pub title: String,
     pub sections: Vec<SidebarSection>,
+    /// Named themes offered by the theme provider
+    pub themes: Vec<ThemeInfo>,
 }
 
 /// Messages from sidebar interactions
 #[derive(Debug, Clone)]
 pub enum SidebarMessage {
+    /// Switch to the first theme of the opposite brightness (for custom sidebars;
+    /// the built-in sidebar leaves theme picking to the toolbar)
+    #[deprecated(note = "the theme is picked in the toolbar")]
     ToggleBrightness,
     SelectStory(String),
     SearchChanged(String),
+    PopOut,
+    ToggleCompare,
+    ToggleEditor,
+}
+
+/// State of the storybook chrome controls shown in the sidebar
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SidebarControls<'a> {
+    /// Show the "Pop Out Story" button (multi-window support and a story selected)
+    pub can_pop_out: bool,
+    /// Show the "Compare Themes" button (the provider has at least two themes)
+    pub can_compare: bool,
+    /// Compare mode is on
+    pub compare: bool,
+    /// The theme editor panel is open
+    pub editing: bool,
+    /// Control highlighted for gamepad navigation
+    pub focused: Option<&'a SidebarFocus>,
+}
+
+/// A chrome control that can hold the gamepad focus: the toolbar's theme picker
+/// or a sidebar control
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SidebarFocus {
+    /// Theme picker of the toolbar (A picks the next theme)
+    Theme,
+    Compare,
+    Editor,
+    PopOut,
+    Search,
+    /// Navigation item of a story, by id
+    Story(String),
+}
+
+/// Id of the story search input
+pub(crate) const SEARCH_INPUT_ID: &str = "icebook-sidebar-search";
+
+/// Id of the scrollable navigation list
+pub(crate) const NAV_LIST_ID: &str = "icebook-sidebar-nav";
+
+/// Controls of the chrome in navigation order: the toolbar's theme picker, then
+/// the sidebar top to bottom
+pub(crate) fn focus_order(
+    config: &SidebarConfig,
+    search_query: &str,
+    controls: SidebarControls<'_>,
+) -> Vec<SidebarFocus> {
+    let mut order = vec![SidebarFocus::Theme];
+    if controls.can_compare {
+        order.push(SidebarFocus::Compare);
+    }
+    order.push(SidebarFocus::Editor);
+    if controls.can_pop_out {
+        order.push(SidebarFocus::PopOut);
+    }
+    order.push(SidebarFocus::Search);
+    order.extend(
+        visible_sections(config, search_query)
+            .flat_map(|(_, items)| items)
+            .map(|item| SidebarFocus::Story(item.id.clone())),
+    );
+    order
+}
+
+/// Sections with items matching the search query (case-insensitive); empty
+/// sections are skipped
+fn visible_sections<'a>(
+    config: &'a SidebarConfig,
+    search_query: &str,
+) -> impl Iterator<Item = (&'a SidebarSection, Vec<&'a NavItem>)> {
+    let query_lower = search_query.to_lowercase();
+
+    config.sections.iter().filter_map(move |section| {
+        let items: Vec<_> = section
+            .items
+            .iter()
+            .filter(|item| {
+                query_lower.is_empty() || item.label.to_lowercase().contains(&query_lower)
+            })
+            .collect();
+        (!items.is_empty()).then_some((section, items))
+    })
 }
 
 /// Render the sidebar with component navigation
@@ -39,6 +126,7 @@ pub fn sidebar<'a>(
     config: &'a SidebarConfig,
     selected: &str,
     search_query: &str,
+    controls: SidebarControls<'_>,
     theme: &'a dyn SidebarTheme,
 ) -> Element<'a, SidebarMessage> {
     let bg_color = theme.sidebar_background();
@@ -47,7 +135,6 @@ pub fn sidebar<'a>(
 
     // Get font configuration from theme
     let title_font = theme.title_font();
-    let button_font = theme.button_font();
 
     let header = text(&config.title)
         .size(theme.title_size())
@@ -55,69 +142,33 @@ pub fn sidebar<'a>(
         .font(title_font.font)
         .shaping(title_font.shaping);
 
-    // Theme toggle button with visible border/background
-    let toggle_bg = theme.hover_background();
-    let toggle_border = text_secondary;
-    let theme_toggle = button(
-        text("Toggle Theme")
-            .color(text_color)
-            .size(theme.button_size())
-            .font(button_font.font)
-            .shaping(button_font.shaping),
-    )
-    .on_press(SidebarMessage::ToggleBrightness)
-    .padding(8)
-    .style(move |_theme, status| {
-        use iced::widget::button;
-        let bg = match status {
-            button::Status::Hovered => {
-                Color::from_rgba(toggle_bg.r, toggle_bg.g, toggle_bg.b, 0.15)
-            }
-            button::Status::Pressed => Color::from_rgba(toggle_bg.r, toggle_bg.g, toggle_bg.b, 0.2),
-            _ => toggle_bg,
-        };
-        button::Style {
-            background: Some(bg.into()),
-            text_color,
-            border: iced::Border {
-                color: toggle_border,
-                width: 1.0,
-                radius: 4.0.into(),
-            },
-            ..Default::default()
-        }
-    });
+    let is_focused = |target: &SidebarFocus| controls.focused == Some(target);
 
     // Search input
+    let search_focused = is_focused(&SidebarFocus::Search);
     let search_input = text_input("Search components...", search_query)
+        .id(SEARCH_INPUT_ID)
         .on_input(SidebarMessage::SearchChanged)
         .padding(8)
-        .width(Length::Fill);
-
-    // Filter query (lowercase for case-insensitive matching)
-    let query_lower = search_query.to_lowercase();
+        .width(Length::Fill)
+        .style(move |_, status| {
+            let mut style = theme.input_style(status);
+            if search_focused {
+                style.border = style.border.color(theme.focus_color()).width(2);
+            }
+            style
+        });
 
     // Build component list from sections (filtered by search)
     let mut components: Column<'a, SidebarMessage> = Column::new().spacing(4);
 
-    for (i, section) in config.sections.iter().enumerate() {
-        // Filter items by search query
-        let filtered_items: Vec<_> = section
-            .items
-            .iter()
-            .filter(|item| {
-                query_lower.is_empty() || item.label.to_lowercase().contains(&query_lower)
-            })
-            .collect();
-
-        // Skip empty sections after filtering
-        if filtered_items.is_empty() {
-            continue;
-        }
-
-        // Add spacing between sections (not before the first one)
+    for (i, (section, filtered_items)) in visible_sections(config, search_query).enumerate() {
+        // Add a divider between visible sections (not before the first one)
         if i > 0 {
-            components = components.push(Space::new().height(16));
+            components = components
+                .push(Space::new().height(8))
+                .push(divider(theme))
+                .push(Space::new().height(8));
         }
 
         // Section header
@@ -125,25 +176,60 @@ pub fn sidebar<'a>(
 
         // Navigation items in this section
         for item in filtered_items {
-            components = components.push(nav_item(&item.id, &item.label, selected, theme));
+            let focused = is_focused(&SidebarFocus::Story(item.id.clone()));
+            components = components.push(nav_item(&item.id, &item.label, selected, focused, theme));
         }
     }
 
     // Wrap components in scrollable
     let scrollable_components = scrollable(components)
+        .id(NAV_LIST_ID)
         .width(Length::Fill)
-        .height(Length::Fill);
-
-    let content = column![
-        header,
-        Space::new().height(8),
-        theme_toggle,
-        Space::new().height(12),
-        search_input,
-        Space::new().height(16),
-        scrollable_components,
-    ]
-    .padding(16);
+        .height(Length::Fill)
+        .style(move |_, status| theme.scrollbar_style(status));
+
+    let mut content = column![header, Space::new().height(8)];
+
+    // Compare button (only with two or more themes to compare)
+    if controls.can_compare {
+        content = content
+            .push(focusable_button(
+                "Compare Themes",
+                controls.compare,
+                is_focused(&SidebarFocus::Compare),
+                SidebarMessage::ToggleCompare,
+                theme,
+            ))
+            .push(Space::new().height(4));
+    }
+
+    content = content.push(focusable_button(
+        "Edit Theme",
+        controls.editing,
+        is_focused(&SidebarFocus::Editor),
+        SidebarMessage::ToggleEditor,
+        theme,
+    ));
+
+    // Pop-out button (only when multiple windows are supported)
+    if controls.can_pop_out {
+        content = content.push(Space::new().height(4)).push(focusable_button(
+            "Pop Out Story",
+            false,
+            is_focused(&SidebarFocus::PopOut),
+            SidebarMessage::PopOut,
+            theme,
+        ));
+    }
+
+    let content = content
+        .push(Space::new().height(12))
+        .push(divider(theme))
+        .push(Space::new().height(12))
+        .push(search_input)
+        .push(Space::new().height(16))
+        .push(scrollable_components)
+        .padding(16);
 
     container(content)
         .width(Length::Fixed(theme.sidebar_width()))
@@ -155,6 +241,61 @@ pub fn sidebar<'a>(
         .into()
 }
 
+/// A bordered button for storybook chrome (sidebar and panels)
+pub(crate) fn chrome_button<'a, Message: Clone + 'a>(
+    label: &'static str,
+    is_selected: bool,
+    message: Message,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, Message> {
+    focusable_button(label, is_selected, false, message, theme)
+}
+
+/// A chrome button with the gamepad focus highlight when focused
+fn focusable_button<'a, Message: Clone + 'a>(
+    label: &'static str,
+    is_selected: bool,
+    is_focused: bool,
+    message: Message,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, Message> {
+    let button_font = theme.button_font();
+
+    button(
+        text(label)
+            .size(theme.button_size())
+            .font(button_font.font)
+            .shaping(button_font.shaping)
+            .width(Length::Fill)
+            .center(),
+    )
+    .on_press(message)
+    .padding([8, 4])
+    .width(Length::Fill)
+    .style(move |_, status| {
+        let mut style = theme.button_style(status, is_selected);
+        if is_focused {
+            style.border = style.border.color(theme.focus_color()).width(2);
+        }
+        style
+    })
+    .into()
+}
+
+/// A horizontal divider between groups of chrome
+pub(crate) fn divider<'a, Message: 'a>(theme: &'a dyn SidebarTheme) -> Element<'a, Message> {
+    let color = theme.divider_color();
+
+    rule::horizontal(1)
+        .style(move |_| rule::Style {
+            color,
+            radius: 0.0.into(),
+            fill_mode: rule::FillMode::Full,
+            snap: true,
+        })
+        .into()
+}
+
 fn section_header<'a>(
     label: &str,
     color: Color,
@@ -173,20 +314,10 @@ fn nav_item<'a>(
     id: &str,
     label: &str,
     selected: &str,
+    is_focused: bool,
     theme: &'a dyn SidebarTheme,
 ) -> Element<'a, SidebarMessage> {
     let is_selected = id == selected;
-    let text_color = if is_selected {
-        theme.text_primary()
-    } else {
-        theme.text_secondary()
-    };
-    let bg_color = if is_selected {
-        theme.selected_background()
-    } else {
-        Color::TRANSPARENT
-    };
-    let hover_bg = theme.hover_background();
     let nav_font = theme.nav_font();
     let nav_size = theme.nav_size();
 
@@ -195,7 +326,6 @@ fn nav_item<'a>(
     let btn = button(
         text(label.to_string())
             .size(nav_size)
-            .color(text_color)
             .font(nav_font.font)
             .shaping(nav_font.shaping),
     )
@@ -203,17 +333,64 @@ fn nav_item<'a>(
     .padding([8, 12])
     .width(Length::Fill)
     .style(move |_, status| {
-        let bg = match status {
-            button::Status::Hovered if !is_selected => hover_bg,
-            _ => bg_color,
-        };
-        button::Style {
-            background: Some(iced::Background::Color(bg)),
-            text_color,
-            border: iced::Border::default().rounded(6),
-            ..Default::default()
+        let mut style = theme.nav_item_style(status, is_selected);
+        if is_focused {
+            style.border = style.border.color(theme.focus_color()).width(2);
         }
+        style
     });
 
     btn.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SidebarConfig {
+        let section = |title: &str, items: &[&str]| SidebarSection {
+            title: title.to_string(),
+            items: items
+                .iter()
+                .map(|label| NavItem {
+                    id: label.to_lowercase(),
+                    label: label.to_string(),
+                })
+                .collect(),
+        };
+
+        SidebarConfig {
+            title: "Stories".to_string(),
+            sections: vec![
+                section("Inputs", &["Button", "Slider"]),
+                section("Empty", &[]),
+                section("Layout", &["Card", "Grid"]),
+            ],
+            themes: Vec::new(),
+        }
+    }
+
+    fn titles(config: &SidebarConfig, query: &str) -> Vec<String> {
+        visible_sections(config, query)
+            .map(|(section, _)| section.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn skips_empty_sections() {
+        assert_eq!(titles(&config(), ""), ["Inputs", "Layout"]);
+    }
+
+    #[test]
+    fn filters_items_case_insensitively() {
+        let config = config();
+        let sections: Vec<_> = visible_sections(&config, "CAR").collect();
+
+        // The first visible section is not the first configured one
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].0.title, "Layout");
+        assert_eq!(sections[0].1.len(), 1);
+        assert_eq!(sections[0].1[0].id, "card");
+        assert!(titles(&config, "nothing").is_empty());
+    }
+}
@@ -2,11 +2,23 @@
 
 use iced::{Element, Subscription};
 
+use crate::canvas::CanvasBackground;
+use crate::gamepad::{GamepadChannel, GamepadEvent};
 use crate::sidebar::{SidebarConfig, SidebarMessage};
 use crate::theme::{SidebarTheme, ThemeProvider};
+use crate::tokens::DesignToken;
+use crate::toolbar::{ToolbarItem, ToolbarValues};
+use crate::transition::TransitionProgress;
 
 /// Metadata for a story, used for sidebar navigation and routing
-#[derive(Debug, Clone)]
+///
+/// Build it with `StoryMeta::new` and the builder methods, so new options don't
+/// break existing stories:
+///
+/// ```rust,ignore
+/// StoryMeta::new("buttons", "Buttons", "Actions").aliases(&["button"])
+/// ```
+#[derive(Debug, Clone, Default)]
 pub struct StoryMeta {
     /// Unique identifier/route for this story (e.g., "buttons")
     pub id: &'static str,
@@ -14,6 +26,139 @@ pub struct StoryMeta {
     pub title: &'static str,
     /// Category for grouping in sidebar (e.g., "Actions")
     pub category: &'static str,
+    /// Previous ids that redirect to this story (e.g., after a rename)
+    pub aliases: &'static [&'static str],
+    /// Canvas background the story opens with (`None` for the theme's background)
+    pub background: Option<CanvasBackground>,
+}
+
+impl StoryMeta {
+    /// Metadata with no aliases, opening on the theme's background
+    pub const fn new(id: &'static str, title: &'static str, category: &'static str) -> Self {
+        Self {
+            id,
+            title,
+            category,
+            aliases: &[],
+            background: None,
+        }
+    }
+
+    /// Previous ids that redirect to this story (e.g., after a rename)
+    pub const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Canvas background the story opens with
+    pub const fn background(mut self, background: CanvasBackground) -> Self {
+        self.background = Some(background);
+        self
+    }
+}
+
+/// Storybook services and state available to stories
+///
+/// Passed to `Story::view_with_context`, `Story::subscription_with_context` and
+/// their `StoryRegistry` counterparts.
+#[derive(Debug, Clone, Default)]
+pub struct StoryContext {
+    theme_transition: Option<TransitionProgress>,
+    /// Gamepad events, for the selected story's subscriptions only
+    gamepad: Option<GamepadChannel>,
+    /// Design tokens of the theme passed to the view, if edited in the theme editor
+    tokens: Option<Vec<DesignToken>>,
+}
+
+impl StoryContext {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with_theme_transition(theme_transition: Option<TransitionProgress>) -> Self {
+        Self {
+            theme_transition,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn with_gamepad(gamepad: GamepadChannel) -> Self {
+        Self {
+            gamepad: Some(gamepad),
+            ..Self::default()
+        }
+    }
+
+    /// Use the theme editor's tokens for the theme passed to the view
+    pub(crate) fn with_edited_tokens(mut self, tokens: Option<Vec<DesignToken>>) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// The running transition of the chrome to the theme passed to the view
+    ///
+    /// `None` when no transition is running (also in subscriptions). Stories can
+    /// animate along with the chrome by blending their colors with `lerp_color`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn view_with_context<'a>(&'a self, theme: &'a T, context: &StoryContext) -> Element<'a, Self::Message> {
+    ///     let background = match context.theme_transition() {
+    ///         Some(transition) => lerp_color(
+    ///             background_of(&transition.from),
+    ///             background_of(&transition.to),
+    ///             transition.progress,
+    ///         ),
+    ///         None => theme.background(),
+    ///     };
+    ///     self.card(background)
+    /// }
+    /// ```
+    pub fn theme_transition(&self) -> Option<TransitionProgress> {
+        self.theme_transition
+    }
+
+    /// Events of the virtual gamepad (D-pad, A/B, shoulder buttons and sticks)
+    ///
+    /// Driven by the on-screen controller panel and its keyboard mapping, shown
+    /// with "Gamepad" in the toolbar, and by `Settings::gamepad`. Only the
+    /// selected story gets events; in pop-out windows this subscribes to nothing.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn subscription_with_context(&self, context: &StoryContext) -> Subscription<Self::Message> {
+    ///     context.gamepad().map(MenuMessage::Gamepad)
+    /// }
+    /// ```
+    pub fn gamepad(&self) -> Subscription<GamepadEvent> {
+        self.gamepad
+            .as_ref()
+            .map_or_else(Subscription::none, GamepadChannel::subscription)
+    }
+
+    /// Design tokens of the theme passed to the view, as edited in the theme editor
+    ///
+    /// `None` while the theme is unedited (also in subscriptions). The theme passed
+    /// to views always holds the provider's values, so stories that read tokens
+    /// from it can prefer these to follow the editor live.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn view_with_context<'a>(&'a self, theme: &'a T, context: &StoryContext) -> Element<'a, Self::Message> {
+    ///     let padding = context
+    ///         .tokens()
+    ///         .and_then(|tokens| tokens.iter().find(|token| token.name == "spacing.md"))
+    ///         .and_then(|token| match token.value {
+    ///             TokenValue::Spacing(pixels) => Some(pixels),
+    ///             _ => None,
+    ///         })
+    ///         .unwrap_or(theme.spacing_md());
+    ///     self.card(padding)
+    /// }
+    /// ```
+    pub fn tokens(&self) -> Option<&[DesignToken]> {
+        self.tokens.as_deref()
+    }
 }
 
 /// Individual story trait for single component documentation
@@ -33,11 +178,7 @@ pub struct StoryMeta {
 ///     type Message = ButtonsMessage;
 ///
 ///     fn meta() -> StoryMeta {
-///         StoryMeta {
-///             id: "buttons",
-///             title: "Buttons",
-///             category: "Actions",
-///         }
+///         StoryMeta::new("buttons", "Buttons", "Actions")
 ///     }
 ///
 ///     fn update(&mut self, message: Self::Message) {
@@ -62,11 +203,27 @@ pub trait Story<Theme: ?Sized> {
     /// Render the story view
     fn view<'a>(&'a self, theme: &'a Theme) -> Element<'a, Self::Message>;
 
+    /// Render the story view, with access to storybook state such as a running
+    /// theme transition. Defaults to `view`.
+    fn view_with_context<'a>(
+        &'a self,
+        theme: &'a Theme,
+        _context: &StoryContext,
+    ) -> Element<'a, Self::Message> {
+        self.view(theme)
+    }
+
     /// Return subscriptions for this story (e.g., for animations)
     /// Default implementation returns no subscriptions.
     fn subscription(&self) -> Subscription<Self::Message> {
         Subscription::none()
     }
+
+    /// Return subscriptions for this story, with access to storybook services
+    /// such as the virtual gamepad. Defaults to `subscription`.
+    fn subscription_with_context(&self, _context: &StoryContext) -> Subscription<Self::Message> {
+        self.subscription()
+    }
 }
 
 /// Registry of all stories in a storybook
@@ -144,6 +301,72 @@ pub trait StoryRegistry: Default {
         theme: &'a <Self::Provider as ThemeProvider>::Theme,
     ) -> Element<'a, Self::Message>;
 
+    /// Custom controls added to the toolbar above the canvas (e.g. locale or density)
+    ///
+    /// Their values are passed to `view_with_toolbar`. Defaults to no items.
+    fn toolbar() -> Vec<ToolbarItem> {
+        Vec::new()
+    }
+
+    /// Render a specific story's view with the values of the toolbar items
+    ///
+    /// Override this instead of relying on `view` for stories that react to
+    /// `toolbar()` items. The default implementation ignores the values.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn view_with_toolbar<'a>(
+    ///     &'a self,
+    ///     story_id: &str,
+    ///     theme: &'a <Self::Provider as ThemeProvider>::Theme,
+    ///     toolbar: &ToolbarValues,
+    /// ) -> Element<'a, Self::Message> {
+    ///     let locale = toolbar.choice("locale").unwrap_or("en");
+    ///     match story_id {
+    ///         "dates" => self.dates.view(theme, locale).map(MyMessage::Dates),
+    ///         _ => self.view(story_id, theme),
+    ///     }
+    /// }
+    /// ```
+    fn view_with_toolbar<'a>(
+        &'a self,
+        story_id: &str,
+        theme: &'a <Self::Provider as ThemeProvider>::Theme,
+        _toolbar: &ToolbarValues,
+    ) -> Element<'a, Self::Message> {
+        self.view(story_id, theme)
+    }
+
+    /// Render a specific story's view with the values of the toolbar items and
+    /// storybook state such as a running theme transition
+    ///
+    /// Defaults to `view_with_toolbar`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn view_with_context<'a>(
+    ///     &'a self,
+    ///     story_id: &str,
+    ///     theme: &'a <Self::Provider as ThemeProvider>::Theme,
+    ///     toolbar: &ToolbarValues,
+    ///     context: &StoryContext,
+    /// ) -> Element<'a, Self::Message> {
+    ///     match story_id {
+    ///         "cards" => self.cards.view_with_context(theme, context).map(MyMessage::Cards),
+    ///         _ => self.view_with_toolbar(story_id, theme, toolbar),
+    ///     }
+    /// }
+    /// ```
+    fn view_with_context<'a>(
+        &'a self,
+        story_id: &str,
+        theme: &'a <Self::Provider as ThemeProvider>::Theme,
+        toolbar: &ToolbarValues,
+        _context: &StoryContext,
+    ) -> Element<'a, Self::Message> {
+        self.view_with_toolbar(story_id, theme, toolbar)
+    }
+
     /// Render the welcome/home view
     /// Override this in your implementation to customize the welcome page.
     /// The default implementation displays a simple "Welcome to {title}" message.
@@ -209,4 +432,30 @@ pub trait StoryRegistry: Default {
     fn subscription(&self, _story_id: &str) -> Subscription<Self::Message> {
         Subscription::none()
     }
+
+    /// Return subscriptions for the currently selected story, with access to
+    /// storybook services such as the virtual gamepad
+    ///
+    /// Defaults to `subscription`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn subscription_with_context(
+    ///     &self,
+    ///     story_id: &str,
+    ///     context: &StoryContext,
+    /// ) -> Subscription<Self::Message> {
+    ///     match story_id {
+    ///         "menu" => self.menu.subscription_with_context(context).map(MyMessage::Menu),
+    ///         _ => self.subscription(story_id),
+    ///     }
+    /// }
+    /// ```
+    fn subscription_with_context(
+        &self,
+        story_id: &str,
+        _context: &StoryContext,
+    ) -> Subscription<Self::Message> {
+        self.subscription(story_id)
+    }
 }
@@ -2,8 +2,14 @@
 //!
 //! Consumers implement `ThemeProvider` to supply their own theme system.
 
+use iced::time::Duration;
+use iced::widget::overlay::menu;
 use iced::widget::text::Shaping;
-use iced::{Color, Font};
+use iced::widget::{button, container, pick_list, scrollable, text_input};
+use iced::{Background, Border, Color, Font, Shadow};
+use serde::Serialize;
+
+use crate::tokens::{serialize_hex, DesignToken};
 
 /// Brightness mode for theme switching
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -23,8 +29,132 @@ impl Brightness {
     }
 }
 
+/// A named theme offered by a `ThemeProvider`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeInfo {
+    /// Stable identifier, used in preferences and URLs (e.g., "high-contrast")
+    pub id: &'static str,
+    /// Display label for the theme picker (e.g., "High Contrast")
+    pub label: &'static str,
+    /// Whether this is a light or dark theme (used when following the system)
+    pub brightness: Brightness,
+}
+
+impl ThemeInfo {
+    /// The default light theme
+    pub const LIGHT: ThemeInfo = ThemeInfo {
+        id: "light",
+        label: "Light",
+        brightness: Brightness::Light,
+    };
+
+    /// The default dark theme
+    pub const DARK: ThemeInfo = ThemeInfo {
+        id: "dark",
+        label: "Dark",
+        brightness: Brightness::Dark,
+    };
+
+    /// The default theme for a brightness
+    pub fn for_brightness(brightness: Brightness) -> Self {
+        match brightness {
+            Brightness::Dark => Self::DARK,
+            Brightness::Light => Self::LIGHT,
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label)
+    }
+}
+
+/// User-selected theme mode: follow the system, or pin a named theme
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ThemeMode {
+    /// Follow the OS/browser color scheme, reacting to changes live
+    #[default]
+    System,
+    /// A specific theme, by `ThemeInfo::id`
+    Named(String),
+    /// The first light theme
+    #[deprecated(note = "use `ThemeMode::Named` with a theme id")]
+    Light,
+    /// The first dark theme
+    #[deprecated(note = "use `ThemeMode::Named` with a theme id")]
+    Dark,
+}
+
+impl ThemeMode {
+    /// Parse a stored theme mode ("system" or a theme id)
+    pub fn from_id(id: &str) -> Self {
+        if id.eq_ignore_ascii_case("system") {
+            ThemeMode::System
+        } else {
+            ThemeMode::Named(id.to_string())
+        }
+    }
+
+    /// Identifier used in preferences and URLs
+    #[allow(deprecated)]
+    pub fn id(&self) -> &str {
+        match self {
+            ThemeMode::System => "system",
+            ThemeMode::Named(id) => id,
+            ThemeMode::Light => "light",
+            ThemeMode::Dark => "dark",
+        }
+    }
+
+    /// Resolve the theme to use from the provider's themes and the system brightness
+    ///
+    /// Unknown ids fall back to following the system, except "light" and "dark",
+    /// which pick the first theme of that brightness (these were the only ids
+    /// before named themes were introduced), like the deprecated `Light` and
+    /// `Dark` modes.
+    #[allow(deprecated)]
+    pub fn resolve(&self, themes: &[ThemeInfo], system: Brightness) -> ThemeInfo {
+        let first_with = |brightness| first_theme_with(themes, brightness);
+
+        match self {
+            ThemeMode::Named(id) => match themes.iter().find(|theme| theme.id == id) {
+                Some(theme) => *theme,
+                None if id.eq_ignore_ascii_case("light") => first_with(Brightness::Light),
+                None if id.eq_ignore_ascii_case("dark") => first_with(Brightness::Dark),
+                None => first_with(system),
+            },
+            ThemeMode::System => first_with(system),
+            ThemeMode::Light => first_with(Brightness::Light),
+            ThemeMode::Dark => first_with(Brightness::Dark),
+        }
+    }
+}
+
+/// First theme with the given brightness, falling back to the first theme
+/// (or the default theme for that brightness when the list is empty)
+pub(crate) fn first_theme_with(themes: &[ThemeInfo], brightness: Brightness) -> ThemeInfo {
+    themes
+        .iter()
+        .find(|theme| theme.brightness == brightness)
+        .or_else(|| themes.first())
+        .copied()
+        .unwrap_or_else(|| ThemeInfo::for_brightness(brightness))
+}
+
+/// Default font size for the sidebar title
+pub const DEFAULT_TITLE_SIZE: f32 = 24.0;
+/// Default font size for section headers
+pub const DEFAULT_SECTION_SIZE: f32 = 12.0;
+/// Default font size for navigation items
+pub const DEFAULT_NAV_SIZE: f32 = 14.0;
+/// Default font size for chrome buttons and the theme picker
+pub const DEFAULT_BUTTON_SIZE: f32 = 14.0;
+/// Default sidebar width
+pub const DEFAULT_SIDEBAR_WIDTH: f32 = 220.0;
+
 /// Font configuration for sidebar text
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SidebarFont {
     pub font: Font,
     pub shaping: Shaping,
@@ -65,7 +195,7 @@ pub trait SidebarTheme {
     }
     /// Font size for the sidebar title
     fn title_size(&self) -> f32 {
-        24.0
+        DEFAULT_TITLE_SIZE
     }
 
     /// Font for section headers
@@ -74,7 +204,7 @@ pub trait SidebarTheme {
     }
     /// Font size for section headers
     fn section_size(&self) -> f32 {
-        12.0
+        DEFAULT_SECTION_SIZE
     }
 
     /// Font for navigation items
@@ -83,21 +213,468 @@ pub trait SidebarTheme {
     }
     /// Font size for navigation items
     fn nav_size(&self) -> f32 {
-        14.0
+        DEFAULT_NAV_SIZE
     }
 
-    /// Font for the theme toggle button
+    /// Font for chrome buttons and the theme picker
     fn button_font(&self) -> SidebarFont {
         SidebarFont::default()
     }
-    /// Font size for the theme toggle button
+    /// Font size for chrome buttons and the theme picker
     fn button_size(&self) -> f32 {
-        14.0
+        DEFAULT_BUTTON_SIZE
     }
 
     /// Sidebar width
     fn sidebar_width(&self) -> f32 {
-        220.0
+        DEFAULT_SIDEBAR_WIDTH
+    }
+
+    // === Widget styles ===
+    //
+    // Defaults are derived from the colors above, so a theme that only sets
+    // colors still gets consistently styled chrome.
+
+    /// Border color of focused inputs, open pickers and the sidebar control
+    /// holding the gamepad focus
+    fn focus_color(&self) -> Color {
+        self.text_primary()
+    }
+
+    /// Color of the dividers between sidebar sections
+    fn divider_color(&self) -> Color {
+        self.hover_background()
+    }
+
+    /// Style of chrome buttons ("Compare Themes", "Edit Theme", ...)
+    fn button_style(&self, status: button::Status, is_selected: bool) -> button::Style {
+        let background = match status {
+            _ if is_selected => self.selected_background(),
+            button::Status::Hovered => self.hover_background(),
+            button::Status::Pressed => self.selected_background(),
+            button::Status::Active | button::Status::Disabled => Color::TRANSPARENT,
+        };
+        let text_color = match status {
+            button::Status::Disabled => self.text_secondary(),
+            _ => self.text_primary(),
+        };
+
+        button::Style {
+            background: Some(background.into()),
+            text_color,
+            border: Border {
+                color: self.text_secondary(),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Style of story links in the navigation list
+    fn nav_item_style(&self, status: button::Status, is_selected: bool) -> button::Style {
+        let background = match status {
+            _ if is_selected => self.selected_background(),
+            button::Status::Hovered => self.hover_background(),
+            button::Status::Pressed => self.selected_background(),
+            button::Status::Active | button::Status::Disabled => Color::TRANSPARENT,
+        };
+        let text_color = if is_selected {
+            self.text_primary()
+        } else {
+            self.text_secondary()
+        };
+
+        button::Style {
+            background: Some(background.into()),
+            text_color,
+            border: Border::default().rounded(6),
+            ..Default::default()
+        }
+    }
+
+    /// Style of text inputs (story search, theme editor fields)
+    fn input_style(&self, status: text_input::Status) -> text_input::Style {
+        let (border_color, border_width) = match status {
+            text_input::Status::Focused { .. } => (self.focus_color(), 2.0),
+            text_input::Status::Hovered => (self.text_primary(), 1.0),
+            text_input::Status::Active | text_input::Status::Disabled => {
+                (self.text_secondary(), 1.0)
+            }
+        };
+        let background = match status {
+            text_input::Status::Disabled => self.hover_background(),
+            _ => self.sidebar_background(),
+        };
+
+        text_input::Style {
+            background: background.into(),
+            border: Border {
+                color: border_color,
+                width: border_width,
+                radius: 4.0.into(),
+            },
+            icon: self.text_secondary(),
+            placeholder: self.text_secondary(),
+            value: self.text_primary(),
+            selection: self.selected_background(),
+        }
+    }
+
+    /// Style of pick lists (theme picker)
+    fn pick_list_style(&self, status: pick_list::Status) -> pick_list::Style {
+        let (border_color, border_width) = match status {
+            pick_list::Status::Opened { .. } => (self.focus_color(), 2.0),
+            pick_list::Status::Hovered => (self.text_primary(), 1.0),
+            pick_list::Status::Active => (self.text_secondary(), 1.0),
+        };
+        let background = match status {
+            pick_list::Status::Hovered => self.hover_background(),
+            _ => Color::TRANSPARENT,
+        };
+
+        pick_list::Style {
+            text_color: self.text_primary(),
+            placeholder_color: self.text_secondary(),
+            handle_color: self.text_secondary(),
+            background: background.into(),
+            border: Border {
+                color: border_color,
+                width: border_width,
+                radius: 4.0.into(),
+            },
+        }
+    }
+
+    /// Style of the menu of an open pick list
+    fn menu_style(&self) -> menu::Style {
+        menu::Style {
+            background: self.sidebar_background().into(),
+            border: Border {
+                color: self.text_secondary(),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            text_color: self.text_primary(),
+            selected_text_color: self.text_primary(),
+            selected_background: self.selected_background().into(),
+            shadow: Shadow::default(),
+        }
+    }
+
+    /// Style of scrollbars in the chrome and around stories
+    fn scrollbar_style(&self, status: scrollable::Status) -> scrollable::Style {
+        let (vertical_active, horizontal_active) = match status {
+            scrollable::Status::Active { .. } => (false, false),
+            scrollable::Status::Hovered {
+                is_vertical_scrollbar_hovered,
+                is_horizontal_scrollbar_hovered,
+                ..
+            } => (
+                is_vertical_scrollbar_hovered,
+                is_horizontal_scrollbar_hovered,
+            ),
+            scrollable::Status::Dragged {
+                is_vertical_scrollbar_dragged,
+                is_horizontal_scrollbar_dragged,
+                ..
+            } => (
+                is_vertical_scrollbar_dragged,
+                is_horizontal_scrollbar_dragged,
+            ),
+        };
+        let rail = |active: bool| scrollable::Rail {
+            background: None,
+            border: Border::default().rounded(2),
+            scroller: scrollable::Scroller {
+                background: if active {
+                    self.text_secondary().into()
+                } else {
+                    self.divider_color().into()
+                },
+                border: Border::default().rounded(2),
+            },
+        };
+
+        scrollable::Style {
+            container: container::Style::default(),
+            vertical_rail: rail(vertical_active),
+            horizontal_rail: rail(horizontal_active),
+            gap: None,
+            auto_scroll: scrollable::AutoScroll {
+                background: Background::Color(self.sidebar_background()),
+                border: Border::default()
+                    .rounded(u32::MAX)
+                    .width(1)
+                    .color(self.text_secondary()),
+                shadow: Shadow::default(),
+                icon: self.text_secondary(),
+            },
+        }
+    }
+}
+
+/// The six colors of a `SidebarTheme`, as plain data
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SidebarColors {
+    #[serde(serialize_with = "serialize_hex")]
+    pub sidebar_background: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    pub text_primary: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    pub text_secondary: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    pub selected_background: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    pub hover_background: Color,
+    #[serde(serialize_with = "serialize_hex")]
+    pub content_background: Color,
+}
+
+impl SidebarColors {
+    /// Field names, in declaration order (also the `SidebarTheme` method names)
+    pub const NAMES: [&'static str; 6] = [
+        "sidebar_background",
+        "text_primary",
+        "text_secondary",
+        "selected_background",
+        "hover_background",
+        "content_background",
+    ];
+
+    /// Read the colors of a sidebar theme
+    pub fn from_theme(theme: &dyn SidebarTheme) -> Self {
+        Self {
+            sidebar_background: theme.sidebar_background(),
+            text_primary: theme.text_primary(),
+            text_secondary: theme.text_secondary(),
+            selected_background: theme.selected_background(),
+            hover_background: theme.hover_background(),
+            content_background: theme.content_background(),
+        }
+    }
+
+    /// Colors in the order of `NAMES`
+    pub fn to_array(&self) -> [Color; 6] {
+        [
+            self.sidebar_background,
+            self.text_primary,
+            self.text_secondary,
+            self.selected_background,
+            self.hover_background,
+            self.content_background,
+        ]
+    }
+
+    /// Mutable access to a color by its index in `NAMES`
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Color> {
+        match index {
+            0 => Some(&mut self.sidebar_background),
+            1 => Some(&mut self.text_primary),
+            2 => Some(&mut self.text_secondary),
+            3 => Some(&mut self.selected_background),
+            4 => Some(&mut self.hover_background),
+            5 => Some(&mut self.content_background),
+            _ => None,
+        }
+    }
+
+    /// Blend towards `other` (`t` from 0.0, `self`, to 1.0, `other`)
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            sidebar_background: lerp_color(self.sidebar_background, other.sidebar_background, t),
+            text_primary: lerp_color(self.text_primary, other.text_primary, t),
+            text_secondary: lerp_color(self.text_secondary, other.text_secondary, t),
+            selected_background: lerp_color(self.selected_background, other.selected_background, t),
+            hover_background: lerp_color(self.hover_background, other.hover_background, t),
+            content_background: lerp_color(self.content_background, other.content_background, t),
+        }
+    }
+}
+
+/// Blend two colors (`t` from 0.0, `a`, to 1.0, `b`), e.g. to follow a theme transition
+pub fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: f32, b: f32| a + (b - a) * t;
+
+    Color {
+        r: mix(a.r, b.r),
+        g: mix(a.g, b.g),
+        b: mix(a.b, b.b),
+        a: mix(a.a, b.a),
+    }
+}
+
+/// A sidebar theme with replaced colors, keeping the base theme's fonts and sizes
+///
+/// Used for live edits and theme transitions. Widget styles are the base theme's,
+/// with each of its six colors swapped for the replacement (other colors, e.g. a
+/// custom focus color, are kept).
+pub(crate) struct RecoloredSidebarTheme {
+    pub(crate) base: &'static dyn SidebarTheme,
+    pub(crate) colors: SidebarColors,
+}
+
+impl RecoloredSidebarTheme {
+    /// Swap a color of the base theme for its replacement
+    fn recolor(&self, color: Color) -> Color {
+        let base = SidebarColors::from_theme(self.base).to_array();
+        match base.iter().position(|base| *base == color) {
+            Some(index) => self.colors.to_array()[index],
+            None => color,
+        }
+    }
+
+    fn recolor_background(&self, background: Background) -> Background {
+        match background {
+            Background::Color(color) => Background::Color(self.recolor(color)),
+            gradient => gradient,
+        }
+    }
+
+    fn recolor_border(&self, border: Border) -> Border {
+        Border {
+            color: self.recolor(border.color),
+            ..border
+        }
+    }
+
+    fn recolor_button(&self, style: button::Style) -> button::Style {
+        button::Style {
+            background: style.background.map(|b| self.recolor_background(b)),
+            text_color: self.recolor(style.text_color),
+            border: self.recolor_border(style.border),
+            ..style
+        }
+    }
+
+    fn recolor_container(&self, style: container::Style) -> container::Style {
+        container::Style {
+            text_color: style.text_color.map(|color| self.recolor(color)),
+            background: style.background.map(|b| self.recolor_background(b)),
+            border: self.recolor_border(style.border),
+            ..style
+        }
+    }
+
+    fn recolor_rail(&self, rail: scrollable::Rail) -> scrollable::Rail {
+        scrollable::Rail {
+            background: rail.background.map(|b| self.recolor_background(b)),
+            border: self.recolor_border(rail.border),
+            scroller: scrollable::Scroller {
+                background: self.recolor_background(rail.scroller.background),
+                border: self.recolor_border(rail.scroller.border),
+            },
+        }
+    }
+}
+
+impl SidebarTheme for RecoloredSidebarTheme {
+    fn sidebar_background(&self) -> Color {
+        self.colors.sidebar_background
+    }
+    fn text_primary(&self) -> Color {
+        self.colors.text_primary
+    }
+    fn text_secondary(&self) -> Color {
+        self.colors.text_secondary
+    }
+    fn selected_background(&self) -> Color {
+        self.colors.selected_background
+    }
+    fn hover_background(&self) -> Color {
+        self.colors.hover_background
+    }
+    fn content_background(&self) -> Color {
+        self.colors.content_background
+    }
+
+    fn title_font(&self) -> SidebarFont {
+        self.base.title_font()
+    }
+    fn title_size(&self) -> f32 {
+        self.base.title_size()
+    }
+    fn section_font(&self) -> SidebarFont {
+        self.base.section_font()
+    }
+    fn section_size(&self) -> f32 {
+        self.base.section_size()
+    }
+    fn nav_font(&self) -> SidebarFont {
+        self.base.nav_font()
+    }
+    fn nav_size(&self) -> f32 {
+        self.base.nav_size()
+    }
+    fn button_font(&self) -> SidebarFont {
+        self.base.button_font()
+    }
+    fn button_size(&self) -> f32 {
+        self.base.button_size()
+    }
+    fn sidebar_width(&self) -> f32 {
+        self.base.sidebar_width()
+    }
+
+    fn focus_color(&self) -> Color {
+        self.recolor(self.base.focus_color())
+    }
+    fn divider_color(&self) -> Color {
+        self.recolor(self.base.divider_color())
+    }
+    fn button_style(&self, status: button::Status, is_selected: bool) -> button::Style {
+        self.recolor_button(self.base.button_style(status, is_selected))
+    }
+    fn nav_item_style(&self, status: button::Status, is_selected: bool) -> button::Style {
+        self.recolor_button(self.base.nav_item_style(status, is_selected))
+    }
+    fn input_style(&self, status: text_input::Status) -> text_input::Style {
+        let style = self.base.input_style(status);
+        text_input::Style {
+            background: self.recolor_background(style.background),
+            border: self.recolor_border(style.border),
+            icon: self.recolor(style.icon),
+            placeholder: self.recolor(style.placeholder),
+            value: self.recolor(style.value),
+            selection: self.recolor(style.selection),
+        }
+    }
+    fn pick_list_style(&self, status: pick_list::Status) -> pick_list::Style {
+        let style = self.base.pick_list_style(status);
+        pick_list::Style {
+            text_color: self.recolor(style.text_color),
+            placeholder_color: self.recolor(style.placeholder_color),
+            handle_color: self.recolor(style.handle_color),
+            background: self.recolor_background(style.background),
+            border: self.recolor_border(style.border),
+        }
+    }
+    fn menu_style(&self) -> menu::Style {
+        let style = self.base.menu_style();
+        menu::Style {
+            background: self.recolor_background(style.background),
+            border: self.recolor_border(style.border),
+            text_color: self.recolor(style.text_color),
+            selected_text_color: self.recolor(style.selected_text_color),
+            selected_background: self.recolor_background(style.selected_background),
+            ..style
+        }
+    }
+    fn scrollbar_style(&self, status: scrollable::Status) -> scrollable::Style {
+        let style = self.base.scrollbar_style(status);
+        scrollable::Style {
+            container: self.recolor_container(style.container),
+            vertical_rail: self.recolor_rail(style.vertical_rail),
+            horizontal_rail: self.recolor_rail(style.horizontal_rail),
+            gap: style.gap.map(|gap| self.recolor_background(gap)),
+            auto_scroll: scrollable::AutoScroll {
+                background: self.recolor_background(style.auto_scroll.background),
+                border: self.recolor_border(style.auto_scroll.border),
+                icon: self.recolor(style.auto_scroll.icon),
+                ..style.auto_scroll
+            },
+        }
     }
 }
 
@@ -106,10 +683,14 @@ pub trait SidebarTheme {
 /// This is the main integration point for custom themes. The `Theme` type
 /// is passed to your story `view()` functions.
 ///
+/// Providers with only a light and a dark theme just implement `get_theme`.
+/// Providers with more themes (e.g., high contrast or branded variants) also
+/// override `themes()` and `get_named_theme()`.
+///
 /// # Example
 ///
 /// ```rust,ignore
-/// use icebook::{Brightness, ThemeProvider, SidebarTheme};
+/// use icebook::{Brightness, ThemeInfo, ThemeProvider, SidebarTheme};
 /// use playtron_themes::ThemeInterface;
 ///
 /// pub struct MyThemeProvider;
@@ -127,6 +708,21 @@ pub trait SidebarTheme {
 ///     fn get_sidebar_theme(brightness: Brightness) -> &'static dyn SidebarTheme {
 ///         Self::get_theme(brightness)
 ///     }
+///
+///     fn themes() -> Vec<ThemeInfo> {
+///         vec![
+///             ThemeInfo::LIGHT,
+///             ThemeInfo::DARK,
+///             ThemeInfo { id: "high-contrast", label: "High Contrast", brightness: Brightness::Dark },
+///         ]
+///     }
+///
+///     fn get_named_theme(theme: &ThemeInfo) -> &'static Self::Theme {
+///         match theme.id {
+///             "high-contrast" => &HIGH_CONTRAST_THEME,
+///             _ => Self::get_theme(theme.brightness),
+///         }
+///     }
 /// }
 /// ```
 pub trait ThemeProvider {
@@ -144,6 +740,54 @@ pub trait ThemeProvider {
             Brightness::Light => &LIGHT_SIDEBAR,
         }
     }
+
+    /// List the named themes shown in the theme picker
+    /// Default implementation offers `ThemeInfo::LIGHT` and `ThemeInfo::DARK`
+    fn themes() -> Vec<ThemeInfo> {
+        vec![ThemeInfo::LIGHT, ThemeInfo::DARK]
+    }
+
+    /// Get the theme for a named theme from `themes()`
+    /// Default implementation uses `get_theme` with the theme's brightness
+    fn get_named_theme(theme: &ThemeInfo) -> &'static Self::Theme {
+        Self::get_theme(theme.brightness)
+    }
+
+    /// Get the sidebar theme for a named theme from `themes()`
+    /// Default implementation uses `get_sidebar_theme` with the theme's brightness
+    fn get_named_sidebar_theme(theme: &ThemeInfo) -> &'static dyn SidebarTheme {
+        Self::get_sidebar_theme(theme.brightness)
+    }
+
+    /// Get the Iced theme for a named theme from `themes()`
+    ///
+    /// Built-in widgets (the sidebar search input, scrollbars, stock widgets inside
+    /// stories) are styled from this theme's palette. Return
+    /// `iced::Theme::custom(theme.label, palette)` to match your design system.
+    /// Default implementation uses `iced::Theme::Light` or `iced::Theme::Dark`.
+    fn get_iced_theme(theme: &ThemeInfo) -> iced::Theme {
+        match theme.brightness {
+            Brightness::Dark => iced::Theme::Dark,
+            Brightness::Light => iced::Theme::Light,
+        }
+    }
+
+    /// List the design tokens (colors, spacing, radii, font sizes) of a named theme
+    ///
+    /// When any theme returns tokens, icebook adds a generated "Design Tokens" story
+    /// with swatches, values and WCAG contrast ratios. Default implementation returns none.
+    fn design_tokens(_theme: &ThemeInfo) -> Vec<DesignToken> {
+        Vec::new()
+    }
+
+    /// Duration of the animated transition of the chrome colors between themes
+    ///
+    /// Return `Duration::ZERO` to switch instantly. On WASM the transition is
+    /// skipped when the browser prefers reduced motion. Stories see the running
+    /// transition through `StoryContext::theme_transition`. Default is 250ms.
+    fn theme_transition_duration() -> Duration {
+        Duration::from_millis(250)
+    }
 }
 
 /// Simple built-in sidebar theme for dark mode
@@ -241,3 +885,100 @@ pub fn default_sidebar_theme(brightness: Brightness) -> &'static dyn SidebarThem
         Brightness::Light => &LIGHT_SIDEBAR,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A theme with custom button and focus styling
+    struct Custom;
+
+    impl SidebarTheme for Custom {
+        fn sidebar_background(&self) -> Color {
+            Color::BLACK
+        }
+        fn text_primary(&self) -> Color {
+            Color::WHITE
+        }
+        fn text_secondary(&self) -> Color {
+            Color::from_rgb(0.5, 0.5, 0.5)
+        }
+        fn selected_background(&self) -> Color {
+            Color::from_rgb(0.2, 0.2, 0.2)
+        }
+        fn hover_background(&self) -> Color {
+            Color::from_rgb(0.1, 0.1, 0.1)
+        }
+        fn content_background(&self) -> Color {
+            Color::from_rgb(0.3, 0.3, 0.3)
+        }
+        fn focus_color(&self) -> Color {
+            Color::from_rgb(1.0, 0.5, 0.0)
+        }
+        fn button_style(&self, status: button::Status, is_selected: bool) -> button::Style {
+            button::Style {
+                text_color: self.text_primary(),
+                border: Border::default().rounded(12).color(self.focus_color()),
+                ..Default::default()
+            }
+            .with_background(if is_selected || status == button::Status::Hovered {
+                self.selected_background()
+            } else {
+                self.sidebar_background()
+            })
+        }
+    }
+
+    static CUSTOM: Custom = Custom;
+
+    fn recolored() -> RecoloredSidebarTheme {
+        RecoloredSidebarTheme {
+            base: &CUSTOM,
+            colors: SidebarColors {
+                text_primary: Color::from_rgb(0.9, 0.0, 0.0),
+                selected_background: Color::from_rgb(0.0, 0.9, 0.0),
+                ..SidebarColors::from_theme(&CUSTOM)
+            },
+        }
+    }
+
+    #[test]
+    fn recolored_theme_keeps_custom_styles() {
+        let style = recolored().button_style(button::Status::Active, true);
+
+        assert_eq!(style.border.radius, 12.0.into());
+        // Custom colors stay, the theme's colors are replaced
+        assert_eq!(style.border.color, Color::from_rgb(1.0, 0.5, 0.0));
+        assert_eq!(style.text_color, Color::from_rgb(0.9, 0.0, 0.0));
+        assert_eq!(
+            style.background,
+            Some(Background::Color(Color::from_rgb(0.0, 0.9, 0.0)))
+        );
+        assert_eq!(recolored().focus_color(), Color::from_rgb(1.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn recolored_theme_recolors_default_styles() {
+        let theme = recolored();
+
+        // Defaults of the base theme are derived from its colors, so they follow
+        let nav = theme.nav_item_style(button::Status::Active, true);
+        assert_eq!(nav.text_color, Color::from_rgb(0.9, 0.0, 0.0));
+        assert_eq!(
+            nav.background,
+            Some(Background::Color(Color::from_rgb(0.0, 0.9, 0.0)))
+        );
+        assert_eq!(theme.divider_color(), CUSTOM.hover_background());
+        assert_eq!(
+            theme.menu_style().text_color,
+            Color::from_rgb(0.9, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn lerps_colors() {
+        let mid = lerp_color(Color::BLACK, Color::WHITE, 0.5);
+        assert_eq!(mid, Color::from_rgb(0.5, 0.5, 0.5));
+        assert_eq!(lerp_color(Color::BLACK, Color::WHITE, 2.0), Color::WHITE);
+    }
+}
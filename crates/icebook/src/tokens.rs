@@ -0,0 +1,425 @@
+//! Design tokens and the generated "Design Tokens" story
+//!
+//! Theme providers can list named tokens (colors, spacing, radii, font sizes)
+//! via `ThemeProvider::design_tokens`. icebook then adds a "Design Tokens" story
+//! with swatches, values and WCAG contrast ratios between text and background colors.
+//!
+//! Tokens can also be imported from W3C design token JSON with `TokenTable`.
+
+use iced::widget::{column, container, row, text, Column, Space};
+use iced::{Color, Element, Length};
+
+use crate::story::StoryMeta;
+use crate::theme::SidebarTheme;
+
+mod w3c;
+
+pub use w3c::{SidebarTokenPaths, Token, TokenData, TokenError, TokenTable};
+
+/// Story id of the generated design tokens page
+pub const DESIGN_TOKENS_STORY_ID: &str = "design-tokens";
+
+/// Metadata of the generated design tokens page
+pub const DESIGN_TOKENS_STORY: StoryMeta =
+    StoryMeta::new(DESIGN_TOKENS_STORY_ID, "Design Tokens", "Theme");
+
+/// How a color token is used, for pairing text and backgrounds in contrast checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorRole {
+    /// Foreground color for text and icons
+    Text,
+    /// Surface/background color
+    Background,
+    /// Any other color (accents, borders, ...)
+    Other,
+}
+
+/// Value of a design token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenValue {
+    Color(Color, ColorRole),
+    /// Spacing in logical pixels
+    Spacing(f32),
+    /// Corner radius in logical pixels
+    Radius(f32),
+    /// Font size in logical pixels
+    FontSize(f32),
+}
+
+/// A named design token
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignToken {
+    /// Token name (e.g., "color.background.primary")
+    pub name: String,
+    pub value: TokenValue,
+}
+
+impl DesignToken {
+    /// A text/foreground color token
+    pub fn text_color(name: impl Into<String>, color: Color) -> Self {
+        Self::new(name, TokenValue::Color(color, ColorRole::Text))
+    }
+
+    /// A background/surface color token
+    pub fn background_color(name: impl Into<String>, color: Color) -> Self {
+        Self::new(name, TokenValue::Color(color, ColorRole::Background))
+    }
+
+    /// Any other color token
+    pub fn color(name: impl Into<String>, color: Color) -> Self {
+        Self::new(name, TokenValue::Color(color, ColorRole::Other))
+    }
+
+    /// A spacing token in logical pixels
+    pub fn spacing(name: impl Into<String>, pixels: f32) -> Self {
+        Self::new(name, TokenValue::Spacing(pixels))
+    }
+
+    /// A corner radius token in logical pixels
+    pub fn radius(name: impl Into<String>, pixels: f32) -> Self {
+        Self::new(name, TokenValue::Radius(pixels))
+    }
+
+    /// A font size token in logical pixels
+    pub fn font_size(name: impl Into<String>, pixels: f32) -> Self {
+        Self::new(name, TokenValue::FontSize(pixels))
+    }
+
+    fn new(name: impl Into<String>, value: TokenValue) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+/// WCAG 2.x relative luminance of a color (alpha is ignored)
+pub fn relative_luminance(color: Color) -> f32 {
+    let channel = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+}
+
+/// WCAG 2.x contrast ratio between two colors, from 1.0 to 21.0
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// WCAG conformance level for a contrast ratio
+fn wcag_rating(ratio: f32) -> &'static str {
+    if ratio >= 7.0 {
+        "AAA"
+    } else if ratio >= 4.5 {
+        "AA"
+    } else if ratio >= 3.0 {
+        "AA Large"
+    } else {
+        "Fail"
+    }
+}
+
+/// Format a color as `#rrggbb` (or `#rrggbbaa` when translucent)
+pub fn color_hex(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+/// Parse a `#rgb`, `#rrggbb` or `#rrggbbaa` color (the `#` is optional)
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    // `from_str_radix` alone would accept signs, e.g. "+f+f+f"
+    if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let short = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|c| c * 17);
+
+    let [r, g, b, a] = match hex.len() {
+        3 => [short(0)?, short(1)?, short(2)?, 255],
+        6 => [channel(0)?, channel(2)?, channel(4)?, 255],
+        8 => [channel(0)?, channel(2)?, channel(4)?, channel(6)?],
+        _ => return None,
+    };
+
+    Some(Color::from_rgba8(r, g, b, f32::from(a) / 255.0))
+}
+
+/// Serialize a color as a hex string (for `#[serde(serialize_with)]`)
+pub(crate) fn serialize_hex<S: serde::Serializer>(
+    color: &Color,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&color_hex(*color))
+}
+
+/// Render the generated design tokens page
+pub fn design_tokens_view<'a, M: 'a>(
+    tokens: Vec<DesignToken>,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, M> {
+    let text_color = theme.text_primary();
+    let text_secondary = theme.text_secondary();
+    let border_color = theme.hover_background();
+
+    let mut page = Column::new()
+        .spacing(16)
+        .push(text("Design Tokens").size(32).color(text_color));
+
+    if tokens.is_empty() {
+        return page
+            .push(
+                text("The current theme does not define any design tokens.")
+                    .size(16)
+                    .color(text_secondary),
+            )
+            .into();
+    }
+
+    let colors: Vec<(&str, Color, ColorRole)> = tokens
+        .iter()
+        .filter_map(|token| match token.value {
+            TokenValue::Color(color, role) => Some((token.name.as_str(), color, role)),
+            _ => None,
+        })
+        .collect();
+
+    let section = |title: &str| {
+        column![
+            Space::new().height(8),
+            text(title.to_string()).size(20).color(text_color)
+        ]
+    };
+    let value_label = move |value: String| text(value).size(14).color(text_secondary).width(120);
+    let name_label = move |name: &str| text(name.to_string()).size(14).color(text_color).width(220);
+
+    // Colors: swatch, name and hex value
+    if !colors.is_empty() {
+        page = page.push(section("Colors"));
+        for (name, color, _) in &colors {
+            page = page.push(
+                row![
+                    swatch(*color, 40.0, 40.0, 0.0, border_color),
+                    name_label(name),
+                    value_label(color_hex(*color)),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    // Contrast: every text color on every background color
+    let text_colors = colors
+        .iter()
+        .filter(|(_, _, role)| *role == ColorRole::Text);
+    let pairs: Vec<_> = text_colors
+        .flat_map(|fg| {
+            colors
+                .iter()
+                .filter(|(_, _, role)| *role == ColorRole::Background)
+                .map(move |bg| (fg, bg))
+        })
+        .collect();
+
+    if !pairs.is_empty() {
+        page = page.push(section("Contrast"));
+        for ((fg_name, fg, _), (bg_name, bg, _)) in pairs {
+            let ratio = contrast_ratio(*fg, *bg);
+            let (fg, bg) = (*fg, *bg);
+            let sample = container(text("Aa").size(16).color(fg))
+                .padding([8, 12])
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(bg)),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                });
+
+            page = page.push(
+                row![
+                    sample,
+                    name_label(&format!("{} on {}", fg_name, bg_name)),
+                    value_label(format!("{:.2}:1", ratio)),
+                    value_label(wcag_rating(ratio).to_string()),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    // Dimension tokens (spacing, radii, font sizes) by kind
+    let dimensions = |select: fn(TokenValue) -> Option<f32>| -> Vec<(&str, f32)> {
+        tokens
+            .iter()
+            .filter_map(|token| select(token.value).map(|pixels| (token.name.as_str(), pixels)))
+            .collect()
+    };
+
+    // Spacing: a bar of the token's length
+    let spacing = dimensions(|value| match value {
+        TokenValue::Spacing(pixels) => Some(pixels),
+        _ => None,
+    });
+    if !spacing.is_empty() {
+        page = page.push(section("Spacing"));
+        for (name, pixels) in spacing {
+            page = page.push(
+                row![
+                    name_label(name),
+                    value_label(format!("{}px", pixels)),
+                    swatch(text_secondary, pixels, 12.0, 0.0, border_color),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    // Radii: a square with the token's corner radius
+    let radii = dimensions(|value| match value {
+        TokenValue::Radius(pixels) => Some(pixels),
+        _ => None,
+    });
+    if !radii.is_empty() {
+        page = page.push(section("Radii"));
+        for (name, pixels) in radii {
+            page = page.push(
+                row![
+                    swatch(border_color, 48.0, 48.0, pixels, text_secondary),
+                    name_label(name),
+                    value_label(format!("{}px", pixels)),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    // Font sizes: sample text at the token's size
+    let font_sizes = dimensions(|value| match value {
+        TokenValue::FontSize(pixels) => Some(pixels),
+        _ => None,
+    });
+    if !font_sizes.is_empty() {
+        page = page.push(section("Font Sizes"));
+        for (name, pixels) in font_sizes {
+            page = page.push(
+                row![
+                    name_label(name),
+                    value_label(format!("{}px", pixels)),
+                    text("The quick brown fox").size(pixels).color(text_color),
+                ]
+                .spacing(16)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+    }
+
+    page.into()
+}
+
+/// A filled rectangle used for color swatches, spacing bars and radius samples
+fn swatch<'a, M: 'a>(
+    color: Color,
+    width: f32,
+    height: f32,
+    radius: f32,
+    border_color: Color,
+) -> Element<'a, M> {
+    container(Space::new())
+        .width(Length::Fixed(width))
+        .height(Length::Fixed(height))
+        .style(move |_| container::Style {
+            background: Some(iced::Background::Color(color)),
+            border: iced::Border {
+                color: border_color,
+                width: 1.0,
+                radius: radius.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_short_colors() {
+        assert_eq!(parse_hex_color("#fff"), Some(Color::WHITE));
+        assert_eq!(
+            parse_hex_color("#f80"),
+            Some(Color::from_rgb8(0xff, 0x88, 0x00))
+        );
+    }
+
+    #[test]
+    fn parses_long_colors() {
+        assert_eq!(
+            parse_hex_color("#1a2B3c"),
+            Some(Color::from_rgb8(0x1a, 0x2b, 0x3c))
+        );
+        assert_eq!(parse_hex_color(" 000000 "), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn parses_colors_with_alpha() {
+        assert_eq!(
+            parse_hex_color("#ff000080"),
+            Some(Color::from_rgba8(0xff, 0x00, 0x00, 128.0 / 255.0))
+        );
+        assert_eq!(parse_hex_color("#ffffffff"), Some(Color::WHITE));
+    }
+
+    #[test]
+    fn rejects_junk() {
+        for value in [
+            "",
+            "#",
+            "#ff",
+            "#ffff",
+            "#fffff",
+            "#fffffff",
+            "#fffffffff",
+            "#ggg",
+            "#12345z",
+            "#+f+f+f",
+            "#-1-1-1",
+            "+ff",
+            "#ff ff ff",
+            "#ffé",
+            "rgb(0,0,0)",
+            "##fff",
+        ] {
+            assert_eq!(parse_hex_color(value), None, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        for value in ["#1a2b3c", "#ff000080", "#000000", "#ffffff"] {
+            let color = parse_hex_color(value).unwrap();
+            assert_eq!(color_hex(color), value);
+        }
+    }
+}
@@ -0,0 +1,895 @@
+//! W3C design token files (Design Tokens Community Group format)
+//!
+//! Parses token JSON as exported from Figma and other design tools into a
+//! `TokenTable`: groups become dot-separated paths, `$type` is inherited from
+//! enclosing groups, and aliases (`"{color.base.white}"`) are resolved.
+//!
+//! ```json
+//! {
+//!   "color": {
+//!     "$type": "color",
+//!     "base": { "white": { "$value": "#ffffff" } },
+//!     "background": { "$value": "{color.base.white}" }
+//!   },
+//!   "spacing": {
+//!     "$type": "dimension",
+//!     "md": { "$value": "16px" }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use iced::Color;
+use serde_json::{Map, Value};
+
+use super::{parse_hex_color, ColorRole, DesignToken};
+use crate::data_theme::DataSidebarTheme;
+use crate::theme::SidebarColors;
+
+/// Pixels per `rem` when converting dimensions
+const REM_PIXELS: f32 = 16.0;
+
+/// Resolved value of a W3C design token
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenData {
+    Color(Color),
+    /// Dimension in logical pixels (`rem` is converted at 16px)
+    Dimension(f32),
+    /// Plain number (also font weights)
+    Number(f32),
+    /// First family of a font family token
+    FontFamily(String),
+    /// Duration in milliseconds
+    Duration(f32),
+    /// Any other type (e.g. composite shadow or typography tokens), as raw JSON
+    Other(String),
+}
+
+/// A token from a W3C design token file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// Dot-separated path of groups and the token name (e.g., "color.background")
+    pub path: String,
+    /// `$type` of the token, its aliased token, or an enclosing group
+    pub token_type: Option<String>,
+    pub value: TokenData,
+    pub description: Option<String>,
+}
+
+/// An error in a token file, with the path of the offending token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenError {
+    /// Token path (empty for errors in the file as a whole)
+    pub path: String,
+    pub message: String,
+}
+
+impl TokenError {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Token paths used to generate a `SidebarTheme` from a `TokenTable`
+///
+/// Colors are required; sizes are used when present. Point these at existing
+/// tokens, or add a `sidebar` group of aliases to the token file.
+#[derive(Debug, Clone, Copy)]
+pub struct SidebarTokenPaths {
+    pub sidebar_background: &'static str,
+    pub text_primary: &'static str,
+    pub text_secondary: &'static str,
+    pub selected_background: &'static str,
+    pub hover_background: &'static str,
+    pub content_background: &'static str,
+    pub title_size: &'static str,
+    pub section_size: &'static str,
+    pub nav_size: &'static str,
+    pub button_size: &'static str,
+    pub sidebar_width: &'static str,
+}
+
+impl Default for SidebarTokenPaths {
+    fn default() -> Self {
+        Self {
+            sidebar_background: "sidebar.background",
+            text_primary: "sidebar.text.primary",
+            text_secondary: "sidebar.text.secondary",
+            selected_background: "sidebar.selected",
+            hover_background: "sidebar.hover",
+            content_background: "content.background",
+            title_size: "sidebar.font-size.title",
+            section_size: "sidebar.font-size.section",
+            nav_size: "sidebar.font-size.nav",
+            button_size: "sidebar.font-size.button",
+            sidebar_width: "sidebar.width",
+        }
+    }
+}
+
+/// Tokens parsed from a W3C design token file, in document order
+///
+/// Stories can query tokens by path:
+///
+/// ```rust,ignore
+/// static TOKENS: Lazy<TokenTable> = Lazy::new(|| {
+///     TokenTable::from_json(include_str!("tokens.json")).expect("valid design tokens")
+/// });
+///
+/// let primary = TOKENS.color("color.primary").unwrap_or(Color::BLACK);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TokenTable {
+    tokens: Vec<Token>,
+    index: HashMap<String, usize>,
+}
+
+impl TokenTable {
+    /// Parse a W3C design token JSON file
+    ///
+    /// Returns every error found, each with the path of the offending token.
+    pub fn from_json(source: &str) -> Result<Self, Vec<TokenError>> {
+        let root: Value = serde_json::from_str(source)
+            .map_err(|error| vec![TokenError::new("", error.to_string())])?;
+        let Value::Object(root) = &root else {
+            return Err(vec![TokenError::new(
+                "",
+                "expected an object of tokens and groups",
+            )]);
+        };
+
+        let mut raw = Vec::new();
+        let mut errors = Vec::new();
+        collect(root, "", None, &mut raw, &mut errors);
+
+        let by_path: HashMap<&str, &RawToken> = raw
+            .iter()
+            .map(|token| (token.path.as_str(), token))
+            .collect();
+
+        let mut table = Self::default();
+        for token in &raw {
+            let resolved =
+                resolve(token, &by_path, &mut Vec::new()).and_then(|(value, token_type)| {
+                    convert(value, token_type)
+                        .map(|value| (value, token_type))
+                        .map_err(|message| TokenError::new(&token.path, message))
+                });
+
+            match resolved {
+                Ok((value, token_type)) => table.push(Token {
+                    path: token.path.clone(),
+                    token_type: token_type.map(str::to_string),
+                    value,
+                    description: token.description.map(str::to_string),
+                }),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(table)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn push(&mut self, token: Token) {
+        self.index.insert(token.path.clone(), self.tokens.len());
+        self.tokens.push(token);
+    }
+
+    /// Look up a token by path
+    pub fn get(&self, path: &str) -> Option<&Token> {
+        self.index.get(path).map(|&i| &self.tokens[i])
+    }
+
+    /// All tokens, in document order
+    pub fn iter(&self) -> impl Iterator<Item = &Token> {
+        self.tokens.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Color of a color token
+    pub fn color(&self, path: &str) -> Option<Color> {
+        match self.get(path)?.value {
+            TokenData::Color(color) => Some(color),
+            _ => None,
+        }
+    }
+
+    /// Logical pixels of a dimension token
+    pub fn dimension(&self, path: &str) -> Option<f32> {
+        match self.get(path)?.value {
+            TokenData::Dimension(pixels) => Some(pixels),
+            _ => None,
+        }
+    }
+
+    /// Value of a number or font weight token
+    pub fn number(&self, path: &str) -> Option<f32> {
+        match self.get(path)?.value {
+            TokenData::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Family of a font family token
+    pub fn font_family(&self, path: &str) -> Option<&str> {
+        match &self.get(path)?.value {
+            TokenData::FontFamily(family) => Some(family),
+            _ => None,
+        }
+    }
+
+    /// Tokens for the generated design tokens page (`ThemeProvider::design_tokens`)
+    ///
+    /// Colors are paired for contrast checks by path: a segment containing "text"
+    /// or "foreground" marks a text color, "background" or "surface" a background
+    /// (the last matching segment wins). Dimensions under "radius" are radii,
+    /// under "font" font sizes, and spacing otherwise. Other types are skipped.
+    pub fn design_tokens(&self) -> Vec<DesignToken> {
+        self.tokens
+            .iter()
+            .filter_map(|token| {
+                let segments = || token.path.rsplit('.').map(str::to_ascii_lowercase);
+                let name = token.path.clone();
+
+                match token.value {
+                    TokenData::Color(color) => Some(match color_role(segments()) {
+                        ColorRole::Text => DesignToken::text_color(name, color),
+                        ColorRole::Background => DesignToken::background_color(name, color),
+                        ColorRole::Other => DesignToken::color(name, color),
+                    }),
+                    TokenData::Dimension(pixels) => {
+                        if segments().any(|s| s.contains("radius") || s.contains("radii")) {
+                            Some(DesignToken::radius(name, pixels))
+                        } else if segments().any(|s| s.contains("font")) {
+                            Some(DesignToken::font_size(name, pixels))
+                        } else {
+                            Some(DesignToken::spacing(name, pixels))
+                        }
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Generate a sidebar theme from the tokens at the given paths
+    pub fn sidebar_theme(&self, paths: &SidebarTokenPaths) -> Result<DataSidebarTheme, TokenError> {
+        let color = |path: &str| {
+            self.color(path).ok_or_else(|| match self.get(path) {
+                Some(_) => TokenError::new(path, "expected a color token for the sidebar theme"),
+                None => TokenError::new(path, "missing color token for the sidebar theme"),
+            })
+        };
+
+        let mut theme = DataSidebarTheme::from_colors(SidebarColors {
+            sidebar_background: color(paths.sidebar_background)?,
+            text_primary: color(paths.text_primary)?,
+            text_secondary: color(paths.text_secondary)?,
+            selected_background: color(paths.selected_background)?,
+            hover_background: color(paths.hover_background)?,
+            content_background: color(paths.content_background)?,
+        });
+
+        let sizes = [
+            (paths.title_size, &mut theme.title_size),
+            (paths.section_size, &mut theme.section_size),
+            (paths.nav_size, &mut theme.nav_size),
+            (paths.button_size, &mut theme.button_size),
+            (paths.sidebar_width, &mut theme.sidebar_width),
+        ];
+        for (path, size) in sizes {
+            if let Some(pixels) = self.dimension(path) {
+                *size = pixels;
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+/// A token before alias resolution
+struct RawToken<'a> {
+    path: String,
+    value: &'a Value,
+    token_type: Option<&'a str>,
+    description: Option<&'a str>,
+}
+
+/// Collect the tokens of a group (recursively), inheriting `$type` from groups
+fn collect<'a>(
+    group: &'a Map<String, Value>,
+    prefix: &str,
+    inherited_type: Option<&'a str>,
+    tokens: &mut Vec<RawToken<'a>>,
+    errors: &mut Vec<TokenError>,
+) {
+    let group_type = group
+        .get("$type")
+        .and_then(Value::as_str)
+        .or(inherited_type);
+
+    for (name, value) in group {
+        // `$type`, `$description`, `$extensions`, ...
+        if name.starts_with('$') {
+            continue;
+        }
+
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        if name.contains(['.', '{', '}']) {
+            errors.push(TokenError::new(
+                &path,
+                "names must not contain '.', '{' or '}'",
+            ));
+            continue;
+        }
+
+        match value {
+            Value::Object(object) if object.contains_key("$value") => tokens.push(RawToken {
+                path,
+                value: &object["$value"],
+                token_type: object.get("$type").and_then(Value::as_str).or(group_type),
+                description: object.get("$description").and_then(Value::as_str),
+            }),
+            Value::Object(object) => collect(object, &path, group_type, tokens, errors),
+            _ => errors.push(TokenError::new(
+                &path,
+                "expected a token (an object with \"$value\") or a group",
+            )),
+        }
+    }
+}
+
+/// Follow aliases to the final value
+///
+/// The type is the first one declared along the alias chain.
+fn resolve<'a>(
+    token: &RawToken<'a>,
+    tokens: &HashMap<&str, &RawToken<'a>>,
+    chain: &mut Vec<String>,
+) -> Result<(&'a Value, Option<&'a str>), TokenError> {
+    let Some(target) = alias_target(token.value) else {
+        return Ok((token.value, token.token_type));
+    };
+
+    chain.push(token.path.clone());
+    let origin = chain[0].clone();
+    if chain.iter().any(|path| path == target) {
+        return Err(TokenError::new(
+            &origin,
+            format!("circular alias {} -> {}", chain.join(" -> "), target),
+        ));
+    }
+    let Some(aliased) = tokens.get(target) else {
+        return Err(TokenError::new(
+            &origin,
+            format!("unresolved alias {{{target}}}"),
+        ));
+    };
+
+    let (value, token_type) = resolve(aliased, tokens, chain)?;
+    Ok((value, token.token_type.or(token_type)))
+}
+
+/// Path of an alias value (`"{group.token}"`)
+fn alias_target(value: &Value) -> Option<&str> {
+    value.as_str()?.strip_prefix('{')?.strip_suffix('}')
+}
+
+/// Convert a resolved value by its type (inferred from the value when untyped)
+fn convert(value: &Value, token_type: Option<&str>) -> Result<TokenData, String> {
+    let invalid = |expected: &str| format!("invalid {expected} value {value}");
+
+    match token_type {
+        Some("color") => parse_color(value)?
+            .map(TokenData::Color)
+            .ok_or_else(|| invalid("color")),
+        Some("dimension") => parse_dimension(value)
+            .map(TokenData::Dimension)
+            .ok_or_else(|| invalid("dimension")),
+        Some("number") => value
+            .as_f64()
+            .map(|number| TokenData::Number(number as f32))
+            .ok_or_else(|| invalid("number")),
+        Some("fontWeight") => parse_font_weight(value)
+            .map(TokenData::Number)
+            .ok_or_else(|| invalid("font weight")),
+        Some("fontFamily") => parse_font_family(value)
+            .map(TokenData::FontFamily)
+            .ok_or_else(|| invalid("font family")),
+        Some("duration") => parse_duration(value)
+            .map(TokenData::Duration)
+            .ok_or_else(|| invalid("duration")),
+        Some(_) => Ok(TokenData::Other(value.to_string())),
+        None => Ok(parse_color(value)?
+            .map(TokenData::Color)
+            .or_else(|| parse_dimension(value).map(TokenData::Dimension))
+            .or_else(|| {
+                value
+                    .as_f64()
+                    .map(|number| TokenData::Number(number as f32))
+            })
+            .unwrap_or_else(|| TokenData::Other(value.to_string()))),
+    }
+}
+
+/// A hex string, or an object with `hex` or sRGB `components` (and `alpha`,
+/// overriding the hex color's own)
+///
+/// `None` when the value is not a color; components or alpha outside 0..=1 are
+/// an error.
+fn parse_color(value: &Value) -> Result<Option<Color>, String> {
+    let unit = |name: &str, value: &Value| match value.as_f64() {
+        Some(number) if (0.0..=1.0).contains(&number) => Ok(Some(number as f32)),
+        Some(number) => Err(format!("{name} {number} out of range 0..=1")),
+        None => Ok(None),
+    };
+
+    match value {
+        Value::String(hex) => Ok(parse_hex_color(hex)),
+        Value::Object(object) => {
+            let alpha = match object.get("alpha") {
+                Some(alpha) => unit("alpha", alpha)?,
+                None => None,
+            };
+            let components = object.get("components").and_then(Value::as_array);
+            let srgb = matches!(
+                object.get("colorSpace").and_then(Value::as_str),
+                None | Some("srgb")
+            );
+
+            match (object.get("hex").and_then(Value::as_str), components) {
+                (Some(hex), _) => Ok(parse_hex_color(hex).map(|color| Color {
+                    a: alpha.unwrap_or(color.a),
+                    ..color
+                })),
+                (None, Some(components)) if srgb && components.len() == 3 => {
+                    let mut channels = [0.0; 3];
+                    for (channel, component) in channels.iter_mut().zip(components) {
+                        match unit("color component", component)? {
+                            Some(value) => *channel = value,
+                            None => return Ok(None),
+                        }
+                    }
+                    let [r, g, b] = channels;
+                    Ok(Some(Color::from_rgba(r, g, b, alpha.unwrap_or(1.0))))
+                }
+                _ => Ok(None),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+/// `"16px"`, `"1.5rem"` or `{ "value": 16, "unit": "px" }`
+fn parse_dimension(value: &Value) -> Option<f32> {
+    let pixels = |number: f32, unit: &str| match unit {
+        "px" => Some(number),
+        "rem" => Some(number * REM_PIXELS),
+        _ => None,
+    };
+
+    match value {
+        Value::String(dimension) => {
+            let (number, unit) = split_unit(dimension, &["px", "rem"])?;
+            pixels(number, unit)
+        }
+        Value::Object(object) => pixels(
+            object.get("value")?.as_f64()? as f32,
+            object.get("unit")?.as_str()?,
+        ),
+        _ => None,
+    }
+}
+
+/// `"200ms"`, `"0.2s"` or `{ "value": 200, "unit": "ms" }`, in milliseconds
+fn parse_duration(value: &Value) -> Option<f32> {
+    let millis = |number: f32, unit: &str| match unit {
+        "ms" => Some(number),
+        "s" => Some(number * 1000.0),
+        _ => None,
+    };
+
+    match value {
+        Value::String(duration) => {
+            let (number, unit) = split_unit(duration, &["ms", "s"])?;
+            millis(number, unit)
+        }
+        Value::Object(object) => millis(
+            object.get("value")?.as_f64()? as f32,
+            object.get("unit")?.as_str()?,
+        ),
+        _ => None,
+    }
+}
+
+/// Split a string like `"2.5e-1rem"` into its number and the first of `units`
+/// it ends with (so the number may use an exponent)
+fn split_unit<'u>(value: &str, units: &[&'u str]) -> Option<(f32, &'u str)> {
+    let value = value.trim();
+    let (number, unit) = units
+        .iter()
+        .find_map(|unit| value.strip_suffix(unit).map(|number| (number, *unit)))?;
+    let number: f32 = number.trim_end().parse().ok()?;
+
+    number.is_finite().then_some((number, unit))
+}
+
+/// A numeric weight (1-1000) or one of the W3C weight names
+fn parse_font_weight(value: &Value) -> Option<f32> {
+    if let Some(weight) = value.as_f64() {
+        return (1.0..=1000.0).contains(&weight).then_some(weight as f32);
+    }
+
+    let weight = match value.as_str()? {
+        "thin" | "hairline" => 100.0,
+        "extra-light" | "ultra-light" => 200.0,
+        "light" => 300.0,
+        "normal" | "regular" | "book" => 400.0,
+        "medium" => 500.0,
+        "semi-bold" | "demi-bold" => 600.0,
+        "bold" => 700.0,
+        "extra-bold" | "ultra-bold" => 800.0,
+        "black" | "heavy" => 900.0,
+        "extra-black" | "ultra-black" => 950.0,
+        _ => return None,
+    };
+    Some(weight)
+}
+
+/// A family name, or the first of a list of names
+fn parse_font_family(value: &Value) -> Option<String> {
+    match value {
+        Value::String(family) => Some(family.clone()),
+        Value::Array(families) => families.first()?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Role of a color token from its path segments (last segment first)
+fn color_role(mut segments: impl Iterator<Item = String>) -> ColorRole {
+    segments
+        .find_map(|segment| {
+            if segment.contains("text") || segment.contains("foreground") {
+                Some(ColorRole::Text)
+            } else if segment.contains("background") || segment.contains("surface") {
+                Some(ColorRole::Background)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(ColorRole::Other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table(source: Value) -> TokenTable {
+        TokenTable::from_json(&source.to_string()).unwrap()
+    }
+
+    fn errors(source: Value) -> Vec<TokenError> {
+        TokenTable::from_json(&source.to_string()).unwrap_err()
+    }
+
+    #[test]
+    fn parses_dimensions() {
+        for (value, pixels) in [
+            (json!("16px"), 16.0),
+            (json!(" 1.5rem "), 24.0),
+            (json!("1e1px"), 10.0),
+            (json!("2.5E-1rem"), 4.0),
+            (json!("-2px"), -2.0),
+            (json!({ "value": 2, "unit": "rem" }), 32.0),
+        ] {
+            assert_eq!(parse_dimension(&value), Some(pixels), "{value}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_dimensions() {
+        for value in [
+            json!("16"),
+            json!("px"),
+            json!("16em"),
+            json!("16 px px"),
+            json!("infpx"),
+            json!("NaNrem"),
+            json!({ "value": 16, "unit": "em" }),
+            json!(16),
+        ] {
+            assert_eq!(parse_dimension(&value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn parses_durations() {
+        for (value, millis) in [
+            (json!("200ms"), 200.0),
+            (json!("0.2s"), 200.0),
+            (json!("2e2ms"), 200.0),
+            (json!({ "value": 1, "unit": "s" }), 1000.0),
+        ] {
+            assert_eq!(parse_duration(&value), Some(millis), "{value}");
+        }
+        assert_eq!(parse_duration(&json!("200")), None);
+        assert_eq!(parse_duration(&json!("2min")), None);
+    }
+
+    #[test]
+    fn resolves_alias_chains() {
+        let tokens = table(json!({
+            "color": {
+                "$type": "color",
+                "base": { "white": { "$value": "#ffffff" } },
+                "surface": { "$value": "{color.base.white}" },
+                "background": { "$value": "{color.surface}", "$description": "Page" }
+            }
+        }));
+
+        let background = tokens.get("color.background").unwrap();
+        assert_eq!(background.value, TokenData::Color(Color::WHITE));
+        assert_eq!(background.token_type.as_deref(), Some("color"));
+        assert_eq!(background.description.as_deref(), Some("Page"));
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.path.as_str())
+                .collect::<Vec<_>>(),
+            ["color.base.white", "color.surface", "color.background"]
+        );
+    }
+
+    #[test]
+    fn inherits_types() {
+        let tokens = table(json!({
+            "size": {
+                "$type": "dimension",
+                "md": { "$value": "16px" },
+                "nested": { "lg": { "$value": "2rem" } },
+                "count": { "$type": "number", "$value": 3 }
+            },
+            "typed": { "$type": "dimension", "$value": "{size.md}" },
+            "untyped": { "$value": "{size.nested.lg}" },
+            "weight": { "$type": "fontWeight", "$value": "bold" }
+        }));
+
+        assert_eq!(tokens.dimension("size.nested.lg"), Some(32.0));
+        assert_eq!(tokens.number("size.count"), Some(3.0));
+        assert_eq!(tokens.dimension("typed"), Some(16.0));
+        // An untyped alias takes the type of the token it points to
+        let untyped = tokens.get("untyped").unwrap();
+        assert_eq!(untyped.token_type.as_deref(), Some("dimension"));
+        assert_eq!(untyped.value, TokenData::Dimension(32.0));
+        assert_eq!(tokens.number("weight"), Some(700.0));
+    }
+
+    #[test]
+    fn infers_untyped_values() {
+        let tokens = table(json!({
+            "color": { "$value": "#000" },
+            "space": { "$value": "4px" },
+            "ratio": { "$value": 1.5 },
+            "label": { "$value": "hello" }
+        }));
+
+        assert_eq!(tokens.color("color"), Some(Color::BLACK));
+        assert_eq!(tokens.dimension("space"), Some(4.0));
+        assert_eq!(tokens.number("ratio"), Some(1.5));
+        assert_eq!(
+            tokens.get("label").unwrap().value,
+            TokenData::Other("\"hello\"".to_string())
+        );
+    }
+
+    #[test]
+    fn detects_alias_cycles() {
+        let errors = errors(json!({
+            "a": { "$value": "{b}" },
+            "b": { "$value": "{a}" },
+            "self": { "$value": "{self}" }
+        }));
+
+        assert_eq!(
+            errors,
+            [
+                TokenError::new("a", "circular alias a -> b -> a"),
+                TokenError::new("b", "circular alias b -> a -> b"),
+                TokenError::new("self", "circular alias self -> self"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_colors() {
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        for (value, color) in [
+            (json!("#f00"), red),
+            (json!({ "hex": "#ff0000" }), red),
+            (
+                json!({ "hex": "#ff000080" }),
+                Color {
+                    a: 128.0 / 255.0,
+                    ..red
+                },
+            ),
+            (
+                json!({ "hex": "#ff000080", "alpha": 0.25 }),
+                Color { a: 0.25, ..red },
+            ),
+            (json!({ "components": [1, 0, 0] }), red),
+            (
+                json!({ "colorSpace": "srgb", "components": [1, 0, 0], "alpha": 0.5 }),
+                Color { a: 0.5, ..red },
+            ),
+        ] {
+            assert_eq!(parse_color(&value), Ok(Some(color)), "{value}");
+        }
+    }
+
+    #[test]
+    fn skips_values_that_are_not_colors() {
+        for value in [
+            json!("16px"),
+            json!({ "components": [1, 0] }),
+            json!({ "colorSpace": "display-p3", "components": [1, 0, 0] }),
+            json!({ "components": [1, "0", 0] }),
+            json!(3),
+        ] {
+            assert_eq!(parse_color(&value), Ok(None), "{value}");
+        }
+    }
+
+    #[test]
+    fn rejects_color_values_out_of_range() {
+        let errors = errors(json!({
+            "color": {
+                "$type": "color",
+                "bytes": { "$value": { "components": [255, 0, 0] } },
+                "negative": { "$value": { "components": [0, -0.5, 0] } },
+                "alpha": { "$value": { "hex": "#fff", "alpha": 2 } }
+            }
+        }));
+
+        assert_eq!(
+            errors,
+            [
+                TokenError::new("color.bytes", "color component 255 out of range 0..=1"),
+                TokenError::new("color.negative", "color component -0.5 out of range 0..=1"),
+                TokenError::new("color.alpha", "alpha 2 out of range 0..=1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_token_errors_by_path() {
+        let errors = errors(json!({
+            "color": {
+                "$type": "color",
+                "bad": { "$value": "#ggg" },
+                "missing": { "$value": "{color.nope}" },
+                "dotted.name": { "$value": "#fff" },
+                "loose": 3
+            },
+            "space": { "$type": "dimension", "$value": "4em" }
+        }));
+
+        assert_eq!(
+            errors,
+            [
+                TokenError::new(
+                    "color.dotted.name",
+                    "names must not contain '.', '{' or '}'"
+                ),
+                TokenError::new(
+                    "color.loose",
+                    "expected a token (an object with \"$value\") or a group"
+                ),
+                TokenError::new("color.bad", "invalid color value \"#ggg\""),
+                TokenError::new("color.missing", "unresolved alias {color.nope}"),
+                TokenError::new("space", "invalid dimension value \"4em\""),
+            ]
+        );
+        assert_eq!(
+            errors[4].to_string(),
+            "space: invalid dimension value \"4em\""
+        );
+    }
+
+    #[test]
+    fn reports_file_errors() {
+        let errors = TokenTable::from_json("[1, 2]").unwrap_err();
+        assert_eq!(
+            errors,
+            [TokenError::new(
+                "",
+                "expected an object of tokens and groups"
+            )]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "expected an object of tokens and groups"
+        );
+
+        let errors = TokenTable::from_json("{").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].path.is_empty());
+    }
+
+    #[test]
+    fn generates_sidebar_themes() {
+        let colors = json!({
+            "$type": "color",
+            "background": { "$value": "#141414" },
+            "text": {
+                "primary": { "$value": "#f2f2f2" },
+                "secondary": { "$value": "#bfbfbf" }
+            },
+            "selected": { "$value": "#ffffff1a" },
+            "hover": { "$value": "#ffffff0d" }
+        });
+        let tokens = table(json!({
+            "sidebar": colors,
+            "content": { "background": { "$value": "#262626" } },
+            "size": { "$type": "dimension", "width": { "$value": "15rem" } }
+        }));
+
+        let paths = SidebarTokenPaths {
+            sidebar_width: "size.width",
+            ..SidebarTokenPaths::default()
+        };
+        let theme = tokens.sidebar_theme(&paths).unwrap();
+        assert_eq!(theme.sidebar_width, 240.0);
+        assert_eq!(theme.title_size, crate::theme::DEFAULT_TITLE_SIZE);
+
+        let paths = SidebarTokenPaths {
+            hover_background: "size.width",
+            ..paths
+        };
+        assert_eq!(
+            tokens.sidebar_theme(&paths).unwrap_err(),
+            TokenError::new("size.width", "expected a color token for the sidebar theme")
+        );
+
+        let paths = SidebarTokenPaths {
+            hover_background: "sidebar.nope",
+            ..paths
+        };
+        assert_eq!(
+            tokens.sidebar_theme(&paths).unwrap_err(),
+            TokenError::new("sidebar.nope", "missing color token for the sidebar theme")
+        );
+    }
+}
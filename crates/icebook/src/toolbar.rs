@@ -0,0 +1,480 @@
+//! Toolbar above the story canvas
+//!
+//! Holds the chrome controls that act on the story being viewed (theme, canvas
+//! controls, reset, copy link, the virtual gamepad, the performance HUD and focus
+//! mode), followed by the consumer's own toolbar items.
+//! Item values are passed to `StoryRegistry::view_with_toolbar` and, on WASM,
+//! kept in the URL so links reproduce them.
+
+use std::collections::BTreeMap;
+
+use iced::widget::{button, column, container, pick_list, text, Row};
+use iced::{Alignment, Element, Length};
+
+use crate::canvas::{canvas_controls, CanvasMessage, StoryCanvas, Viewport};
+use crate::routing;
+use crate::sidebar::divider;
+use crate::theme::{SidebarTheme, ThemeInfo, ThemeMode};
+
+/// A control the consumer adds to the toolbar, e.g. a locale or density switch
+///
+/// # Example
+///
+/// ```rust,ignore
+/// fn toolbar() -> Vec<ToolbarItem> {
+///     vec![
+///         ToolbarItem::choice("locale", "Locale", &["en", "fr", "ja"]),
+///         ToolbarItem::toggle("compact", "Compact"),
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolbarItem {
+    /// Identifier used to look up the value (and in URLs)
+    pub id: &'static str,
+    /// Text shown in the toolbar
+    pub label: &'static str,
+    pub kind: ToolbarKind,
+}
+
+/// Kind of a toolbar item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarKind {
+    /// On/off button, off by default
+    Toggle,
+    /// Picker between options, the first one by default
+    Choice(&'static [&'static str]),
+}
+
+impl ToolbarItem {
+    /// An on/off button
+    ///
+    /// # Panics
+    /// If `id` contains `,` or `:`, which separate values in URLs.
+    pub const fn toggle(id: &'static str, label: &'static str) -> Self {
+        assert!(
+            !contains_separator(id),
+            "toolbar item ids must not contain ',' or ':'"
+        );
+
+        Self {
+            id,
+            label,
+            kind: ToolbarKind::Toggle,
+        }
+    }
+
+    /// A picker between options (the first one is the default)
+    ///
+    /// # Panics
+    /// If `id` contains `,` or `:`, or an option contains `,`, as these separate
+    /// values in URLs.
+    pub const fn choice(
+        id: &'static str,
+        label: &'static str,
+        options: &'static [&'static str],
+    ) -> Self {
+        assert!(
+            !contains_separator(id),
+            "toolbar item ids must not contain ',' or ':'"
+        );
+        let mut i = 0;
+        while i < options.len() {
+            assert!(
+                !contains_byte(options[i], b','),
+                "toolbar options must not contain ','"
+            );
+            i += 1;
+        }
+
+        Self {
+            id,
+            label,
+            kind: ToolbarKind::Choice(options),
+        }
+    }
+
+    /// Value of the item before the user changes it
+    pub fn default_value(&self) -> ToolbarValue {
+        match self.kind {
+            ToolbarKind::Toggle => ToolbarValue::Toggle(false),
+            ToolbarKind::Choice(options) => {
+                ToolbarValue::Choice(options.first().copied().unwrap_or_default())
+            }
+        }
+    }
+
+    /// Parse a value as written in URLs ("on"/"off" or one of the options)
+    fn parse_value(&self, value: &str) -> Option<ToolbarValue> {
+        match self.kind {
+            ToolbarKind::Toggle => match value {
+                "on" => Some(ToolbarValue::Toggle(true)),
+                "off" => Some(ToolbarValue::Toggle(false)),
+                _ => None,
+            },
+            ToolbarKind::Choice(options) => options
+                .iter()
+                .find(|option| **option == value)
+                .map(|option| ToolbarValue::Choice(option)),
+        }
+    }
+}
+
+/// Whether an item id contains a separator of URL query values
+const fn contains_separator(id: &str) -> bool {
+    contains_byte(id, b',') || contains_byte(id, b':')
+}
+
+const fn contains_byte(value: &str, byte: u8) -> bool {
+    let bytes = value.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == byte {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Value of a toolbar item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarValue {
+    Toggle(bool),
+    Choice(&'static str),
+}
+
+impl std::fmt::Display for ToolbarValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Toggle(true) => f.write_str("on"),
+            Self::Toggle(false) => f.write_str("off"),
+            Self::Choice(option) => f.write_str(option),
+        }
+    }
+}
+
+/// Current values of the consumer's toolbar items, by item id
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolbarValues {
+    values: BTreeMap<&'static str, ToolbarValue>,
+}
+
+impl ToolbarValues {
+    /// Default values of the given items
+    pub fn defaults(items: &[ToolbarItem]) -> Self {
+        Self {
+            values: items
+                .iter()
+                .map(|item| (item.id, item.default_value()))
+                .collect(),
+        }
+    }
+
+    /// Value of an item (`None` for unknown ids)
+    pub fn get(&self, id: &str) -> Option<ToolbarValue> {
+        self.values.get(id).copied()
+    }
+
+    /// Whether a toggle item is on (`false` for unknown ids and choices)
+    pub fn is_on(&self, id: &str) -> bool {
+        self.get(id) == Some(ToolbarValue::Toggle(true))
+    }
+
+    /// Option picked in a choice item (`None` for unknown ids and toggles)
+    pub fn choice(&self, id: &str) -> Option<&'static str> {
+        match self.get(id)? {
+            ToolbarValue::Choice(option) => Some(option),
+            ToolbarValue::Toggle(_) => None,
+        }
+    }
+
+    pub(crate) fn set(&mut self, id: &'static str, value: ToolbarValue) {
+        self.values.insert(id, value);
+    }
+
+    /// Defaults overridden by a URL query value, e.g. `locale:fr,compact:on`
+    ///
+    /// Unknown items and invalid values are ignored.
+    pub(crate) fn from_query(items: &[ToolbarItem], query: &str) -> Self {
+        let mut values = Self::defaults(items);
+        for (id, value) in query.split(',').filter_map(|pair| pair.split_once(':')) {
+            let parsed = items
+                .iter()
+                .find(|item| item.id == id)
+                .and_then(|item| Some((item.id, item.parse_value(value)?)));
+            if let Some((id, value)) = parsed {
+                values.set(id, value);
+            }
+        }
+        values
+    }
+
+    /// URL query value of the items that differ from their defaults (`None` if none do)
+    pub(crate) fn to_query(&self, items: &[ToolbarItem]) -> Option<String> {
+        let changed: Vec<String> = items
+            .iter()
+            .filter_map(|item| {
+                let value = self.get(item.id)?;
+                (value != item.default_value()).then(|| format!("{}:{}", item.id, value))
+            })
+            .collect();
+
+        (!changed.is_empty()).then(|| changed.join(","))
+    }
+}
+
+/// Messages from the toolbar
+#[derive(Debug, Clone)]
+pub enum ToolbarMessage {
+    SetThemeMode(ThemeMode),
+    Canvas(CanvasMessage),
+    /// A consumer toolbar item changed
+    Set(&'static str, ToolbarValue),
+    /// Reset the canvas and toolbar items to their defaults
+    Reset,
+    /// Copy a link to the story as shown to the clipboard (native: the launch
+    /// arguments that reopen it)
+    CopyLink,
+    /// Hide the chrome and go fullscreen (`F`)
+    ToggleFocus,
+    /// Show or hide the virtual gamepad
+    ToggleGamepad,
+    /// Show or hide the performance HUD (`P`)
+    TogglePerf,
+}
+
+/// State of the chrome controls shown in the toolbar
+#[derive(Debug, Clone, Copy)]
+pub struct ToolbarControls<'a> {
+    pub theme_mode: &'a ThemeMode,
+    /// The theme picker holds the gamepad focus
+    pub theme_focused: bool,
+    /// The virtual gamepad is on
+    pub gamepad: bool,
+    /// The performance HUD is shown
+    pub perf: bool,
+}
+
+/// An entry in the theme picker: "System" or one of the named themes
+#[derive(Debug, Clone, PartialEq)]
+struct ThemeOption {
+    mode: ThemeMode,
+    label: String,
+}
+
+impl std::fmt::Display for ThemeOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// Render the toolbar; controls wrap onto more lines in narrow windows
+pub(crate) fn toolbar<'a>(
+    canvas: &'a StoryCanvas,
+    viewports: &'static [Viewport],
+    themes: &[ThemeInfo],
+    controls: ToolbarControls<'_>,
+    items: &'a [ToolbarItem],
+    values: &'a ToolbarValues,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, ToolbarMessage> {
+    let button_font = theme.button_font();
+    let group = || Row::new().spacing(8).align_y(Alignment::Center);
+
+    // Theme picker ("System" followed by the provider's named themes)
+    let theme_options: Vec<ThemeOption> = std::iter::once(ThemeOption {
+        mode: ThemeMode::System,
+        label: "System".to_string(),
+    })
+    .chain(themes.iter().map(|info| ThemeOption {
+        mode: ThemeMode::Named(info.id.to_string()),
+        label: info.label.to_string(),
+    }))
+    .collect();
+    let selected_theme = theme_options
+        .iter()
+        .find(|option| option.mode == *controls.theme_mode)
+        .cloned();
+    let theme_focused = controls.theme_focused;
+    let theme_picker = pick_list(theme_options, selected_theme, |option| {
+        ToolbarMessage::SetThemeMode(option.mode)
+    })
+    .text_size(theme.button_size())
+    .font(button_font.font)
+    .text_shaping(button_font.shaping)
+    .padding([6, 8])
+    .width(Length::Fixed(160.0))
+    .style(move |_, status| {
+        let mut style = theme.pick_list_style(status);
+        if theme_focused {
+            style.border = style.border.color(theme.focus_color()).width(2);
+        }
+        style
+    })
+    .menu_style(move |_| theme.menu_style());
+
+    let gamepad = controls.gamepad;
+    let perf = controls.perf;
+    let mut controls = Row::new()
+        .spacing(16)
+        .align_y(Alignment::Center)
+        .push(theme_picker);
+
+    for group in canvas_controls(canvas, viewports, theme) {
+        controls = controls.push(group.map(ToolbarMessage::Canvas));
+    }
+
+    // Consumer items
+    for item in items {
+        let control: Element<'a, ToolbarMessage> = match item.kind {
+            ToolbarKind::Toggle => {
+                let on = values.is_on(item.id);
+                toolbar_button(
+                    item.label,
+                    on,
+                    Some(ToolbarMessage::Set(item.id, ToolbarValue::Toggle(!on))),
+                    theme,
+                )
+            }
+            ToolbarKind::Choice(options) => group()
+                .push(
+                    text(item.label)
+                        .size(theme.button_size())
+                        .color(theme.text_secondary()),
+                )
+                .push(
+                    pick_list(options, values.choice(item.id), |option| {
+                        ToolbarMessage::Set(item.id, ToolbarValue::Choice(option))
+                    })
+                    .text_size(theme.button_size())
+                    .font(button_font.font)
+                    .text_shaping(button_font.shaping)
+                    .padding([6, 8])
+                    .style(move |_, status| theme.pick_list_style(status))
+                    .menu_style(move |_| theme.menu_style()),
+                )
+                .into(),
+        };
+        controls = controls.push(control);
+    }
+
+    controls = controls.push(
+        group()
+            .push(toolbar_button(
+                "Reset",
+                false,
+                Some(ToolbarMessage::Reset),
+                theme,
+            ))
+            .push(toolbar_button(
+                routing::SHARE_LABEL,
+                false,
+                Some(ToolbarMessage::CopyLink),
+                theme,
+            ))
+            .push(toolbar_button(
+                "Gamepad",
+                gamepad,
+                Some(ToolbarMessage::ToggleGamepad),
+                theme,
+            ))
+            .push(toolbar_button(
+                "Perf",
+                perf,
+                Some(ToolbarMessage::TogglePerf),
+                theme,
+            ))
+            .push(toolbar_button(
+                "Focus",
+                false,
+                Some(ToolbarMessage::ToggleFocus),
+                theme,
+            )),
+    );
+
+    let bg_color = theme.sidebar_background();
+
+    column![
+        container(controls.wrap().vertical_spacing(8))
+            .padding([8, 16])
+            .width(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(bg_color)),
+                ..Default::default()
+            }),
+        divider(theme),
+    ]
+    .into()
+}
+
+/// A compact chrome button for the toolbar; disabled without a message
+pub(crate) fn toolbar_button<'a, Message: Clone + 'a>(
+    label: impl text::IntoFragment<'a>,
+    is_selected: bool,
+    message: Option<Message>,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, Message> {
+    let button_font = theme.button_font();
+
+    button(
+        text(label)
+            .size(theme.button_size())
+            .font(button_font.font)
+            .shaping(button_font.shaping),
+    )
+    .on_press_maybe(message)
+    .padding([6, 12])
+    .style(move |_, status| theme.button_style(status, is_selected))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: &[ToolbarItem] = &[
+        ToolbarItem::choice("locale", "Locale", &["en", "fr", "pt:BR"]),
+        ToolbarItem::toggle("compact", "Compact"),
+        ToolbarItem::choice("density", "Density", &["normal", "dense"]),
+    ];
+
+    #[test]
+    fn defaults_have_no_query() {
+        assert_eq!(ToolbarValues::defaults(ITEMS).to_query(ITEMS), None);
+    }
+
+    #[test]
+    fn round_trips_changed_values() {
+        let mut values = ToolbarValues::defaults(ITEMS);
+        values.set("locale", ToolbarValue::Choice("pt:BR"));
+        values.set("compact", ToolbarValue::Toggle(true));
+
+        let query = values.to_query(ITEMS).unwrap();
+        assert_eq!(query, "locale:pt:BR,compact:on");
+        assert_eq!(ToolbarValues::from_query(ITEMS, &query), values);
+    }
+
+    #[test]
+    fn ignores_unknown_items_and_values() {
+        let values = ToolbarValues::from_query(ITEMS, "theme:dark,locale:de,compact,density:dense");
+
+        assert_eq!(values.choice("locale"), Some("en"));
+        assert!(!values.is_on("compact"));
+        assert_eq!(values.choice("density"), Some("dense"));
+        assert_eq!(values.get("theme"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "toolbar options must not contain ','")]
+    fn rejects_options_with_commas() {
+        let options: &'static [&'static str] = &["a,b"];
+        ToolbarItem::choice("pair", "Pair", options);
+    }
+
+    #[test]
+    #[should_panic(expected = "toolbar item ids must not contain ',' or ':'")]
+    fn rejects_ids_with_separators() {
+        ToolbarItem::toggle("a:b", "A");
+    }
+}
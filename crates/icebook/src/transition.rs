@@ -0,0 +1,166 @@
+//! Animated transitions of the chrome colors between themes
+
+use iced::time::{Duration, Instant};
+
+use crate::theme::{RecoloredSidebarTheme, SidebarColors, SidebarTheme, ThemeInfo};
+
+/// A theme transition of the chrome in progress, passed to story views through
+/// `StoryContext::theme_transition`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitionProgress {
+    /// Theme the chrome is leaving
+    pub from: ThemeInfo,
+    /// Theme the chrome is moving to (the theme passed to the view)
+    pub to: ThemeInfo,
+    /// Eased progress, from 0.0 (showing `from`) to 1.0 (showing `to`)
+    pub progress: f32,
+}
+
+/// A running transition from the previous theme's chrome colors to the current theme
+pub(crate) struct ThemeTransition {
+    pub(crate) from: ThemeInfo,
+    pub(crate) to: ThemeInfo,
+    from_colors: SidebarColors,
+    /// Set on the first frame, so a slow first frame doesn't skip the animation
+    started: Option<Instant>,
+    duration: Duration,
+    /// Eased progress of the last frame (0.0 to 1.0)
+    pub(crate) progress: f32,
+    /// Chrome colors of the last frame
+    pub(crate) sidebar: RecoloredSidebarTheme,
+}
+
+impl ThemeTransition {
+    /// Start a transition from the chrome colors currently shown
+    ///
+    /// `target` is the sidebar theme of `to`, whose fonts and sizes apply right away.
+    pub(crate) fn new(
+        from: ThemeInfo,
+        from_colors: SidebarColors,
+        to: ThemeInfo,
+        target: &'static dyn SidebarTheme,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            from_colors,
+            started: None,
+            duration,
+            progress: 0.0,
+            sidebar: RecoloredSidebarTheme {
+                base: target,
+                colors: from_colors,
+            },
+        }
+    }
+
+    /// Advance to a frame, blending towards `to_colors`; returns the eased progress
+    pub(crate) fn advance(&mut self, now: Instant, to_colors: SidebarColors) -> f32 {
+        let started = *self.started.get_or_insert(now);
+        let linear = if self.duration.is_zero() {
+            1.0
+        } else {
+            (now.saturating_duration_since(started).as_secs_f32() / self.duration.as_secs_f32())
+                .min(1.0)
+        };
+
+        self.progress = ease_in_out(linear);
+        self.sidebar.colors = self.from_colors.lerp(&to_colors, self.progress);
+        self.progress
+    }
+
+    /// Progress of the last frame, as seen by stories
+    pub(crate) fn state(&self) -> TransitionProgress {
+        TransitionProgress {
+            from: self.from,
+            to: self.to,
+            progress: self.progress,
+        }
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.progress >= 1.0
+    }
+}
+
+/// Cubic ease-in-out
+fn ease_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::{default_sidebar_theme, Brightness};
+
+    fn transition(duration: Duration) -> (ThemeTransition, SidebarColors) {
+        let from = SidebarColors::from_theme(default_sidebar_theme(Brightness::Light));
+        let to_theme = default_sidebar_theme(Brightness::Dark);
+        let transition =
+            ThemeTransition::new(ThemeInfo::LIGHT, from, ThemeInfo::DARK, to_theme, duration);
+        (transition, SidebarColors::from_theme(to_theme))
+    }
+
+    fn assert_close(colors: SidebarColors, expected: SidebarColors) {
+        for (color, expected) in colors.to_array().into_iter().zip(expected.to_array()) {
+            let channels = |c: iced::Color| [c.r, c.g, c.b, c.a];
+            for (channel, expected) in channels(color).into_iter().zip(channels(expected)) {
+                assert!(
+                    (channel - expected).abs() < 1e-6,
+                    "{colors:?} != {expected:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn eases_in_and_out() {
+        assert_eq!(ease_in_out(0.0), 0.0);
+        assert_eq!(ease_in_out(0.5), 0.5);
+        assert_eq!(ease_in_out(1.0), 1.0);
+        assert!(ease_in_out(0.25) < 0.25);
+        assert!(ease_in_out(0.75) > 0.75);
+        assert!((ease_in_out(0.25) + ease_in_out(0.75) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn starts_on_the_first_frame() {
+        let (mut transition, to) = transition(Duration::from_millis(200));
+        let start = Instant::now();
+
+        assert_eq!(transition.advance(start, to), 0.0);
+        assert_eq!(transition.sidebar.colors, transition.from_colors);
+        assert!(!transition.is_finished());
+
+        assert_eq!(
+            transition.advance(start + Duration::from_millis(100), to),
+            0.5
+        );
+        assert_eq!(transition.state().progress, 0.5);
+    }
+
+    #[test]
+    fn clamps_at_the_end() {
+        let (mut transition, to) = transition(Duration::from_millis(200));
+        let start = Instant::now();
+        transition.advance(start, to);
+
+        assert_eq!(transition.advance(start + Duration::from_secs(1), to), 1.0);
+        assert_close(transition.sidebar.colors, to);
+        assert!(transition.is_finished());
+    }
+
+    #[test]
+    fn finishes_at_once_without_duration() {
+        let (mut transition, to) = transition(Duration::ZERO);
+
+        assert_eq!(transition.advance(Instant::now(), to), 1.0);
+        assert_close(transition.sidebar.colors, to);
+        assert!(transition.is_finished());
+    }
+}
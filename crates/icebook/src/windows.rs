@@ -0,0 +1,86 @@
+//! Pop-out story windows (native multi-window)
+//!
+//! A story can be popped out of the main window into its own native window.
+//! Each window keeps its own theme, so the same story can be compared
+//! in light and dark or at different sizes side by side.
+
+use iced::widget::{column, container, pick_list, row, scrollable, text, Space};
+use iced::{Element, Length};
+
+use crate::theme::{SidebarTheme, ThemeInfo};
+
+/// Initial size of a pop-out window
+#[cfg(not(target_arch = "wasm32"))]
+pub const POP_OUT_WINDOW_SIZE: iced::Size = iced::Size::new(800.0, 600.0);
+
+/// A story shown in its own native window
+#[derive(Debug, Clone)]
+pub struct StoryWindow {
+    /// Story displayed in this window
+    pub story_id: String,
+    /// Story title, used for the window title
+    pub title: String,
+    /// Theme of this window, independent of the main window
+    pub theme: ThemeInfo,
+}
+
+/// Render a pop-out window: a slim header bar with a theme picker above the story
+pub fn story_window_view<'a, Message: Clone + 'a>(
+    window: &'a StoryWindow,
+    content: Element<'a, Message>,
+    themes: &'a [ThemeInfo],
+    on_select_theme: impl Fn(ThemeInfo) -> Message + 'a,
+    theme: &'a dyn SidebarTheme,
+) -> Element<'a, Message> {
+    let header_bg = theme.sidebar_background();
+    let content_bg = theme.content_background();
+    let text_color = theme.text_primary();
+    let title_font = theme.section_font();
+    let button_font = theme.button_font();
+
+    let theme_picker = pick_list(themes, Some(window.theme), on_select_theme)
+        .text_size(theme.button_size())
+        .font(button_font.font)
+        .text_shaping(button_font.shaping)
+        .padding([4, 8])
+        .style(move |_, status| theme.pick_list_style(status))
+        .menu_style(move |_| theme.menu_style());
+
+    let header = container(
+        row![
+            text(&window.title)
+                .size(theme.nav_size())
+                .color(text_color)
+                .font(title_font.font)
+                .shaping(title_font.shaping),
+            Space::new().width(Length::Fill),
+            theme_picker,
+        ]
+        .align_y(iced::Alignment::Center),
+    )
+    .padding([8, 16])
+    .width(Length::Fill)
+    .style(move |_| container::Style {
+        background: Some(iced::Background::Color(header_bg)),
+        ..Default::default()
+    });
+
+    let body = scrollable(
+        container(content)
+            .padding(32)
+            .width(Length::Fill)
+            .height(Length::Shrink),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(move |_, status| theme.scrollbar_style(status));
+
+    container(column![header, body])
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(move |_| container::Style {
+            background: Some(iced::Background::Color(content_bg)),
+            ..Default::default()
+        })
+        .into()
+}